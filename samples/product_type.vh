@@ -1,4 +1,4 @@
-!allow redefinition
+!allow V0023
 -- Consider the C code:
 {-
 	#include <stdio.h>
@@ -1,4 +1,7 @@
 use ::valhallac;
+use valhallac::{Diagnostics, SourceMap, SourceId};
+use valhallac::lints::{Lint, Level, LintLevels};
+use valhallac::site::Site;
 
 use std::env;
 use std::{fs::File, path::Path};
@@ -18,13 +21,36 @@ fn is_vh_file(filename : &String) -> bool {
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Flags {
     Verbose, Out,
-    Version
+    Version, ErrorFormat,
+    Explain,
+    Allow, Warn, Deny
+}
+
+/// Give a lint the level of a `-A', `-W' or `-D' option.
+fn set_lint(lints : &mut LintLevels, flag : Flags, name : &str) {
+    let (level, option) = match flag {
+        Flags::Allow => (Level::Allow, "-A"),
+        Flags::Warn  => (Level::Warn,  "-W"),
+        _            => (Level::Deny,  "-D"),
+    };
+    match name.parse::<Lint>() {
+        Ok(lint) => lints.set(lint, level, Site::new(),
+                              &format!("{} {}", option, name)),
+        Err(_) => {
+            argument_error(format!("`{}' is not a lint, expected `warnings', \
+                a warning kind (e.g. `TypeWarn') or a code (e.g. `V0002').",
+                name));
+            std::process::exit(1)
+        }
+    }
 }
 
 // TODO: Halt on unrecognised options.
-/// Collect flags and options passed to the executable.
-fn collect_flags() -> HashMap<Flags, String> {
+/// Collect flags and options passed to the executable,
+/// and the levels of lints given by them.
+fn collect_flags() -> (HashMap<Flags, String>, LintLevels) {
     let mut map = HashMap::new();
+    let mut lints = LintLevels::new();
     let dummy = String::new();
 
     let mut maybe_argument : Option<Flags> = None;
@@ -35,8 +61,12 @@ fn collect_flags() -> HashMap<Flags, String> {
         let arg_str = arg.to_string();
 
         if let Some(argument) = maybe_argument {
-            // Key assuredly exists.
-            *map.get_mut(&argument).unwrap() = arg_str;
+            if let Flags::Allow | Flags::Warn | Flags::Deny = argument {
+                set_lint(&mut lints, argument, &arg_str);
+            } else {
+                // Key assuredly exists.
+                *map.get_mut(&argument).unwrap() = arg_str;
+            }
             maybe_argument = None;
             continue;  // Not an option, but an argument.
         }
@@ -44,14 +74,40 @@ fn collect_flags() -> HashMap<Flags, String> {
         let mut singleton = |flag: Flags| map.insert(flag, dummy.clone());
 
         if arg_str.starts_with("--") {
+            // Options may also be given as `--name=argument'.
+            let option = arg_str.trim_start_matches("--");
+            if let Some((name, argument)) = option.split_once('=') {
+                let flag = match name {
+                    "error-format" => Some(Flags::ErrorFormat),
+                    "explain" => Some(Flags::Explain),
+                    _ => None
+                };
+                if let Some(flag) = flag {
+                    map.insert(flag, argument.to_owned());
+                    continue;
+                }
+            }
             let name = arg_str.get(2..);
             match name {
                 Some("verbose") => singleton(Flags::Verbose),
                 Some("version") => singleton(Flags::Version),
+                Some("deny-warnings") => {
+                    lints.set(Lint::Warnings, Level::Deny, Site::new(),
+                              "--deny-warnings");
+                    None
+                },
                 Some("out") => {
                     maybe_argument = Some(Flags::Out);
                     singleton(Flags::Out)
                 },
+                Some("error-format") => {
+                    maybe_argument = Some(Flags::ErrorFormat);
+                    singleton(Flags::ErrorFormat)
+                },
+                Some("explain") => {
+                    maybe_argument = Some(Flags::Explain);
+                    singleton(Flags::Explain)
+                },
                 Some(&_) | None => None
             };
         } else if arg_str.starts_with('-') {
@@ -65,6 +121,9 @@ fn collect_flags() -> HashMap<Flags, String> {
                         singleton(Flags::Out)
                     },
                     "V" => singleton(Flags::Version),
+                    "A" => { maybe_argument = Some(Flags::Allow); None },
+                    "W" => { maybe_argument = Some(Flags::Warn);  None },
+                    "D" => { maybe_argument = Some(Flags::Deny);  None },
                     chr => {
                         argument_error(
                             format!("`-{}' option does not exist.", chr));
@@ -74,7 +133,7 @@ fn collect_flags() -> HashMap<Flags, String> {
             }
         }
     }
-    map
+    (map, lints)
 }
 
 macro_rules! not_debug {
@@ -89,6 +148,17 @@ fn argument_error(msg : impl fmt::Display) {
     println!("{} {}", "[**]".red().bold(), msg.to_string().bold());
 }
 
+/// Keep the issues of a failed compilation step.
+fn collect<T>(result : Result<T, Diagnostics>, issues : &mut Diagnostics) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(mut errors) => {
+            issues.append(&mut errors);
+            None
+        }
+    }
+}
+
 lazy_static! {
     static ref INFO : String = format!("{}", " :: ".bold().white());
 }
@@ -99,7 +169,11 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut args = env::args();
     args.next();
 
-    let flags = collect_flags();
+    // `valhallac fix FILES...' applies the repairs suggested
+    //  by issues to the files, instead of writing any binary.
+    let fixing = env::args().nth(1).as_deref() == Some("fix");
+
+    let (flags, lints) = collect_flags();
 
     if flags.contains_key(&Flags::Version) {
         let (major, minor, tiny) = valhallac::VERSION;
@@ -108,6 +182,32 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    if let Some(code) = flags.get(&Flags::Explain) {
+        let explanation = code.parse::<valhallac::codes::Code>().ok()
+            .and_then(|code| code.explanation());
+        match explanation {
+            Some(explanation) => print!("{}", explanation),
+            None => {
+                argument_error(format!(
+                    "`{}' is not a valid issue code (e.g. `V0010').", code));
+                std::process::exit(1)
+            }
+        }
+        return Ok(());
+    }
+
+    // How issues are written to stderr.
+    let json = match flags.get(&Flags::ErrorFormat).map(String::as_str) {
+        None | Some("human") => false,
+        Some("json") => true,
+        Some(format) => {
+            argument_error(format!(
+                "`{}' is not an error format, expected `human' or `json'.",
+                format));
+            std::process::exit(1)
+        }
+    };
+
     #[allow(unused_variables)]
     let verbose : bool = flags.contains_key(&Flags::Verbose);
 
@@ -123,6 +223,10 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let begin = Instant::now();
+    // Issues of every file, reported once all files are compiled.
+    let mut issues = Diagnostics::new();
+    // Sources of every file, from which issues are shown.
+    let mut sources = SourceMap::new();
 
     for file in files {
         not_debug!(verbose, {
@@ -130,14 +234,14 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
                      "Parsing".bold().blue(),
                      file.underline().white());
         });
-        // Parse source into tree.
-        let root = valhallac::parse(&file);
-        unsafe {
-            if !valhallac::PANIC_MESSAGE.is_empty() {
-                // An error in parsing, halt compilation.
-                panic!("Parse error, will not compile bad tree.");
-            }
-        }
+        // Parse source into tree, an error in
+        //   parsing halts compilation of this file.
+        let mut root = match collect(valhallac::parse(&file, &mut sources), &mut issues) {
+            Some(root) => root,
+            None => continue
+        };
+        // Warnings of the file, kept apart until their levels are applied.
+        let mut found = std::mem::take(&mut root.issues);
 
         // Then compile into series of instructions,
         //   stored as a code block.
@@ -145,7 +249,18 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("{}{}", *INFO,
                      "Compiling".bold().blue());
         });
-        let block = valhallac::compile(&root);
+        let mut compiled = collect(valhallac::compile(&root), &mut found);
+        if let Some(block) = &mut compiled {
+            found.append(&mut block.issues);
+        }
+        // Warnings denied on the command line stop the binary being written.
+        lints.apply(&mut found);
+        let denied = found.has_errors();
+        issues.append(&mut found);
+        let block = match compiled {
+            Some(block) if !denied && !fixing => block,
+            _ => continue
+        };
 
         // Pick name of outfile.
         let out = if let Some(out_location) = flags.get(&Flags::Out) {
@@ -161,7 +276,10 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Convert code block to byte-stream, which will be
         //   the file's contents.
-        let bytes = valhallac::binary_blob(&block);
+        let bytes = match collect(valhallac::binary_blob(&block), &mut issues) {
+            Some(bytes) => bytes,
+            None => continue
+        };
 
         // Write blob to file.
         let mut file = File::create(&out)?;
@@ -174,6 +292,48 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
         });
     }
 
+    // Issues of files which could not be parsed have no levels applied yet.
+    lints.apply(&mut issues);
+
+    if fixing {
+        for (id, content, applied) in valhallac::fix::fix_sources(&issues, &sources) {
+            if let SourceId::File(path) = id {
+                File::create(&path)?.write_all(content.as_bytes())?;
+                println!("{}{} {} issue{} in `{}'.", *INFO,
+                    "Fixed".bold().blue(), applied,
+                    if applied == 1 { "" } else { "s" },
+                    path.to_string_lossy().underline().white());
+            }
+        }
+        // Only show the issues left to fix by hand.
+        issues.retain(|issue| issue.edits.is_empty());
+    }
+
+    issues.sort();
+    if json {
+        // One object per line, without colouring.
+        for issue in &issues {
+            eprintln!("{}", issue.to_json());
+        }
+    } else {
+        eprint!("{}", issues.with_sources(&sources));
+        if !issues.is_empty() {
+            eprintln!(" {} For more about an issue, try `{}'.",
+                "::".white().bold(), "valhallac --explain CODE".bold());
+        }
+        let fixable = issues.iter()
+            .filter(|issue| !issue.edits.is_empty())
+            .count();
+        if fixable > 0 {
+            eprintln!(" {} {} of these may be fixed with `{}'.",
+                "::".white().bold(), fixable,
+                "valhallac fix".bold());
+        }
+    }
+    if issues.has_errors() {
+        std::process::exit(1);
+    }
+
 
     #[allow(unused_variables)] {
         let elapsed = begin.elapsed();
@@ -0,0 +1,442 @@
+use std::fmt;
+use std::str::FromStr;
+
+use unindent::unindent;
+
+/// Stable code identifying a specific issue, e.g. `V0010'.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Code(pub u16);
+
+/// Long-form description of an issue, shown by `valhallac --explain'.
+pub struct Explanation {
+    pub code : Code,
+    /// One line summary of the issue.
+    pub summary : &'static str,
+    pub text : &'static str,
+    /// Source code causing the issue.
+    pub example : &'static str,
+}
+
+impl Code {
+    pub fn explanation(&self) -> Option<&'static Explanation> {
+        EXPLANATIONS.iter().find(|explanation| explanation.code == *self)
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "V{:04}", self.0)
+    }
+}
+
+/// Parses codes written as `V0010', `v0010' or `10'.
+impl FromStr for Code {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s : &str) -> Result<Self, Self::Err> {
+        s.trim_start_matches(['V', 'v'])
+            .parse().map(Code)
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}: {}\n", self.code, self.summary)?;
+        writeln!(f, "{}", unindent(self.text).trim_end())?;
+        if self.example.is_empty() {
+            return Ok(());
+        }
+        writeln!(f, "\nExample:\n")?;
+        for line in unindent(self.example).trim_end().lines() {
+            writeln!(f, "    {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Declares every code, along with its explanation.
+macro_rules! codes {
+    ($($code:ident = $number:literal {
+        $summary:literal, $text:literal, $example:literal
+    })*) => {
+        $(pub const $code : Code = Code($number);)*
+
+        pub const EXPLANATIONS : &[Explanation] = &[
+            $(Explanation {
+                code: $code,
+                summary: $summary,
+                text: $text,
+                example: $example
+            },)*
+        ];
+    };
+}
+
+codes! {
+    V0001 = 1 {
+        "Source file could not be read.",
+        "The file given to the compiler does not exist, or could
+         not be opened for reading (e.g. insufficient permissions).",
+        "$ valhallac does_not_exist.vh"
+    }
+    V0002 = 2 {
+        "Symbol looks like a smiley-face.",
+        "The symbol `:)' is lexed as a symbol named `)', which is
+         rarely intended.  Write the symbol as `:\")\"' if it was.",
+        "x = :)"
+    }
+    V0003 = 3 {
+        "Token cannot begin an expression.",
+        "The token has no null-denotation, that is, it can only
+         appear after another expression (e.g. an infix operator or
+         closing bracket), not at the beginning of one.",
+        "xs = ]"
+    }
+    V0004 = 4 {
+        "Source ended in the middle of an expression.",
+        "The parser expected more tokens, but reached the end of the
+         source.  This is often a missing closing bracket.",
+        "a = (1 + 2"
+    }
+    V0005 = 5 {
+        "Unexpected token.",
+        "A specific token was expected to follow (e.g. a closing
+         parenthesis), but a different one was found.",
+        "a = (1 + 2;"
+    }
+    V0006 = 6 {
+        "Left of a type annotation is not an identifier.",
+        "The member-of operator `:' declares the set that a variable
+         belongs to.  Only variable names may be annotated this way,
+         not arbitrary expressions.",
+        "(f x) : Nat"
+    }
+    V0007 = 7 {
+        "Type annotation is missing its left side.",
+        "The member-of operator `:' needs a variable on its left,
+         which is declared to be a member of the set on its right.",
+        ": Nat"
+    }
+    V0008 = 8 {
+        "Right of a type annotation is not a set.",
+        "A variable can only be declared to be a member of a set,
+         such as `Nat', `Int', `Rat', `Real' or `A -> B'.",
+        "a : 3"
+    }
+    V0009 = 9 {
+        "Variable has multiple type signatures.",
+        "Only functions may be overloaded with more than one type
+         signature, any other variable must belong to a single set.",
+        "a : Nat
+         a : Real"
+    }
+    V0010 = 10 {
+        "Variable is used, but has not been declared.",
+        "Every variable must be declared, by assigning to it,
+         or annotating it with a type, before it may be used.
+         This is often a misspelt name.",
+        "a = 3
+         b = c + 1"
+    }
+    V0011 = 11 {
+        "Value cannot be applied as a function.",
+        "Juxtaposition of two values applies the first to the
+         second, which is only defined when the first is a function.",
+        "a : Nat
+         a = 3
+         b = a 4"
+    }
+    V0012 = 12 {
+        "Function applied to an argument of the wrong type.",
+        "The argument of a function must be an element of the set
+         that the function maps from, as given by its signature.",
+        "f : Int -> Int
+         f n = n - 20
+         a = f 3.5"
+    }
+    V0013 = 13 {
+        "Function does not map from a set.",
+        "The domain of a function type must be a set.",
+        "f : 3 -> Nat"
+    }
+    V0014 = 14 {
+        "Function does not map to a set.",
+        "The codomain of a function type must be a set.",
+        "f : Nat -> 3"
+    }
+    V0015 = 15 {
+        "Mapping between values that are not sets.",
+        "The arrow `->' creates the set of functions between two sets,
+         both of its sides have to be sets.",
+        "f : 1 -> 2"
+    }
+    V0016 = 16 {
+        "Assigned value does not match the type signature.",
+        "The value assigned to a variable must be an element of
+         the set the variable was annotated with.",
+        "a : Nat
+         a = 2.5"
+    }
+    V0017 = 17 {
+        "Cannot assign to this structure.",
+        "Only variables and function definitions (and in the future,
+         patterns) may appear on the left of an assignment.",
+        "3 = a"
+    }
+    V0018 = 18 {
+        "Function definition is not on an identifier.",
+        "When defining a function with `f x = ...', the function
+         being defined (`f') must be an identifier.",
+        "(1 + 2) x = x"
+    }
+    V0019 = 19 {
+        "Function definition has no type annotation.",
+        "Every function definition must be preceded by an annotation
+         giving the function's type signature.",
+        "f n = n + 1"
+    }
+    V0020 = 20 {
+        "Function defined on a variable that is not a function.",
+        "A definition like `f x = ...' defines a function, but `f' was
+         annotated as being an element of some set other than a
+         set of functions.",
+        "f : Nat
+         f x = x"
+    }
+    V0021 = 21 {
+        "Function definition has too many arguments.",
+        "The function takes more arguments in its definition than
+         its type signature allows.",
+        "f : Nat -> Nat
+         f a b = a"
+    }
+    V0022 = 22 {
+        "Function body does not match the type signature.",
+        "The right hand side of a function definition must be an
+         element of the set that the function maps to.",
+        "f : Nat -> Nat
+         f n = 0.5"
+    }
+    V0023 = 23 {
+        "Cannot mutate a bound value.",
+        "Variables are bound once, and cannot be assigned to again.
+         Use a new name for the new value instead.",
+        "a : Nat
+         a = 1
+         a = 2"
+    }
+    V0024 = 24 {
+        "Variable has no type annotation.",
+        "The set a variable is a member of must be stated with a
+         type annotation before it is assigned to.",
+        "a = 3"
+    }
+    V0025 = 25 {
+        "Value cannot be printed.",
+        "The internal `__raw_print' function can only display
+         natural, integer, rational, real and string values.",
+        "__raw_print :sym"
+    }
+    V0026 = 26 {
+        "Unknown type to cast to.",
+        "Values may only be cast to `Nat', `Int', `Rat' or `Real'.",
+        "a = 3 cast String"
+    }
+    V0027 = 27 {
+        "Unknown type to cast from.",
+        "Only natural, integer, rational and real values may be cast.",
+        "a = \"3\" cast Nat"
+    }
+    V0028 = 28 {
+        "Cast type is not a type-name.",
+        "The right side of `cast' must be the name of the set
+         to cast to, e.g. `Nat', `Int', `Rat' or `Real'.",
+        "a = 3 cast (1 + 2)"
+    }
+    V0030 = 30 {
+        "Unknown lint in pragma.",
+        "The `!allow' and `!deny' pragmas take a list of lints, each
+         of which is either `warnings' (meaning every warning), a kind
+         of warning (`LexWarn', `ParseWarn', `TypeWarn' or `CompWarn'),
+         or the code of a specific warning.  Errors, such as binding
+         a variable a second time, cannot be allowed.",
+        "!allow smileys"
+    }
+    V0031 = 31 {
+        "Block comment is never closed.",
+        "Block comments begin with `--*' and end with `*--', or begin
+         with `{-' and end with `-}'.  Block comments may be nested, so
+         every comment opened inside of a comment must also be closed.",
+        "--* Outer comment --* inner comment *--
+         a = 3"
+    }
+    V0032 = 32 {
+        "Indentation mixes tabs and spaces inconsistently.",
+        "Lines in an indentation block (after `do:', `where:', `with:',
+         `let:' or `in:') are compared by their leading whitespace.  When
+         one line is indented with tabs and another with spaces, it cannot
+         be known which is indented further.",
+        "a : Nat
+         a = f x where:
+         \tx = 2
+                 y = 3"
+    }
+    V0033 = 33 {
+        "Indentation does not match any enclosing block.",
+        "A line indented less than the statements of its block closes
+         the block, and must then line up with the statements of an
+         enclosing block.",
+        "a : Nat
+         a = f x where:
+             x = g y where:
+                 y = 2
+               z = 3"
+    }
+    V0034 = 34 {
+        "Value cannot be interpolated into a string.",
+        "Expressions embedded in a string, with `:{...}' or `#{...}',
+         must be strings, or numbers which are shown as strings.",
+        "f : Nat -> Nat
+         f n = n + 1
+         s = \"f is :{f}\""
+    }
+    V0035 = 35 {
+        "String is never closed.",
+        "A string literal or quoted symbol (e.g. `:\"a b\"'), or an
+         expression embedded in a string, has no closing quote (or
+         brace).  The string is taken to end with the line it began on,
+         so the rest of the source may still be read.",
+        "s = \"Hello, World!"
+    }
+    V0036 = 36 {
+        "Malformed escape sequence in string.",
+        "Strings may contain the escapes `\\\\', `\\\"', `\\'', `\\r', `\\n',
+         `\\t', `\\b' and `\\0'; `\\:', `\\#', `\\{' and `\\}' to write
+         interpolation characters literally; `\\xHH' for a character with
+         two hexadecimal digits, and `\\u{H...}' for any Unicode character,
+         with one to six hexadecimal digits.",
+        "s = \"C:\\Users\""
+    }
+    V0037 = 37 {
+        "Division by zero in a constant expression.",
+        "Arithmetic on constants is performed by the compiler, but a
+         division by zero is not, and is left to fail at runtime.",
+        "a : Real
+         a = 1.0 / 0.0"
+    }
+    V0038 = 38 {
+        "Constant expression overflows.",
+        "Arithmetic on constants is performed by the compiler, but a
+         result too large to be represented as a real (or a number too
+         large to be cast to a real) is left to be computed at runtime.",
+        "a : Real
+         a = 1.0e300 * 1.0e300"
+    }
+    V0039 = 39 {
+        "Constant expression is not a number.",
+        "Arithmetic on constants is performed by the compiler, but an
+         operation on reals with no meaningful result (NaN), such as
+         subtracting infinities, is left to be computed at runtime.",
+        "a : Real
+         a = 1.0e400 - 1.0e400"
+    }
+    V0040 = 40 {
+        "Malformed numeric literal.",
+        "Numbers are written in decimal (`255', `2.5', `25e-1'), or with
+         a prefix in hexadecimal (`0xFF'), octal (`0o377') or binary
+         (`0b1111_1111').  Digits may be separated by single underscores,
+         and a number may not be directly followed by letters or digits
+         outside of its base.  Decimal numbers without a fractional part
+         or negative exponent are exact naturals, so their exponent may
+         be at most 10000.",
+        "a = 0b102"
+    }
+    V0041 = 41 {
+        "Malformed set-builder.",
+        "A set-builder, e.g. `[ n : Nat => n mod 3 == 0 ]' or
+         `[ 3*n => n <- Nat ]', is the only element between its brackets.
+         Left of its `=>' is the expression giving each element, or a
+         variable annotated with the set it ranges over.  Right of it are
+         predicates, and variables drawn from sets with `<-', separated
+         by commas.  Every set-builder must bind at least one variable.",
+        "Evens = [ 2*n => n > 0 ]"
+    }
+    V0042 = 42 {
+        "Variable of a set-builder does not range over a set.",
+        "The variables of a set-builder must be drawn from sets, such as
+         `Nat' or `[ 1; 2; 3 ]'.",
+        "Evens = [ 2*n => n <- 3 ]"
+    }
+    V0043 = 43 {
+        "Index is out of range.",
+        "Elements of tuples and vectors are indexed from zero, so the
+         last element of an `n' element tuple has an index of `n - 1'.",
+        "p : Int * String
+         p = (-3, \"John\")
+         name : String
+         name = p[2]"
+    }
+    V0044 = 44 {
+        "Cannot index this value.",
+        "Only tuples and vectors may be indexed, with natural numbers,
+         e.g. `p[0]', or equivalently `index 0 p'.",
+        "a : Nat
+         a = 3
+         b : Nat
+         b = a[0]"
+    }
+    V0045 = 45 {
+        "Invalid pattern.",
+        "The arguments of a function defined by cases are patterns, which
+         are either literals, variables, the wildcard `_', constructors
+         (starting with a capital letter, and possibly applied to
+         patterns), or tuples of patterns.",
+        "f : Nat -> Nat
+         f (n + 1) = n"
+    }
+    V0046 = 46 {
+        "Pattern cannot match the argument.",
+        "A pattern never matches a value of the argument's type, e.g. a
+         string can never match a natural number, nor can a tuple of
+         two elements match a tuple of three.",
+        "f : Nat * Nat -> Nat
+         f (a, b, c) = a"
+    }
+    V0047 = 47 {
+        "Variable is bound twice by a pattern.",
+        "Each variable of a clause's patterns may only be bound once, as
+         patterns do not compare the values they match with each other.",
+        "same : Nat -> Nat -> Sym
+         same x x = :true"
+    }
+    V0048 = 48 {
+        "Block cannot be compiled yet.",
+        "Only `do:' blocks, whose statements are run in turn to give the
+         value of the last of them, can be compiled.  Blocks opened by
+         `where:', `with:', `let:' or `in:' are not supported yet.",
+        "a : Nat
+         a = x where:
+             x = 2"
+    }
+}
+
+#[cfg(test)]
+mod catalogue {
+    use super::*;
+
+    #[test]
+    fn codes_are_unique_and_ordered() {
+        for pair in EXPLANATIONS.windows(2) {
+            assert!(pair[0].code < pair[1].code,
+                "`{}' is declared before `{}'.", pair[0].code, pair[1].code);
+        }
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        for explanation in EXPLANATIONS {
+            let code = explanation.code;
+            assert_eq!(code.to_string().parse::<Code>(), Ok(code));
+        }
+    }
+}
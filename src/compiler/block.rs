@@ -1,9 +1,12 @@
 #[cfg(feature="debug")]
 use std::fmt;
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use crate::issue;
+use issue::{Diagnostics, Fallible};
+
+use crate::site::Site;
 
 use crate::syntax;
 use syntax::ast;
@@ -11,6 +14,7 @@ use syntax::ast::{Nodes, StaticTypes};
 
 use super::element;
 use super::instructions;
+use super::types;
 
 use element::{Element, Symbol};
 use instructions::{Instr, Operators};
@@ -18,6 +22,79 @@ use num_traits::cast::FromPrimitive;
 
 use super::internal_functions;
 
+use crate::suggest;
+
+/// Value of a node known at compile time, if it is a constant.
+fn constant_element(node : &Nodes) -> Option<Element<'_>> {
+    Some(match node {
+        Nodes::Num(num_node) => numerics_to_element(&num_node.value),
+        Nodes::Str(str_node) => Element::EString(&str_node.value),
+        Nodes::Sym(sym_node) => Element::ESymbol(Symbol::new(&sym_node.value)),
+        Nodes::Nil(_) => Element::ENil,
+        Nodes::Vec(ast::VecNode { elements, .. })
+        | Nodes::Tuple(ast::TupleNode { elements, .. }) => Element::ETuple(elements.iter()
+            .map(constant_element)
+            .collect::<Option<_>>()?),
+        _ => return None
+    })
+}
+
+/// Function being applied, and the operands it is applied to,
+/// e.g. `f' and `1', `(a, b)' of `f 1 (a, b)'.
+fn applied(call : &ast::CallNode) -> (&Nodes, Vec<&Nodes>) {
+    let mut operands = vec![&call.operands[0]];
+    let mut callee = &*call.callee;
+    while let Nodes::Call(partial) = callee {
+        operands.push(&partial.operands[0]);
+        callee = &*partial.callee;
+    }
+    operands.reverse();
+    (callee, operands)
+}
+
+/// Left and right of the definition of a function, e.g. `f (a, b) = a'.
+fn definition(node : &Nodes) -> Option<(&ast::CallNode, &Nodes)> {
+    let call = node.call().filter(|call| call.is_binary())?;
+    let partial = call.callee.call()?;
+    if partial.callee.ident()?.value != "=" {
+        return None;
+    }
+    Some((partial.operands[0].call()?, &call.operands[0]))
+}
+
+/// Consecutive definitions of the function defined first in `nodes'.
+fn clauses(nodes : &[Nodes]) -> Vec<(&ast::CallNode, &Nodes)> {
+    let name = |(left, _) : &(&ast::CallNode, &Nodes)|
+        applied(left).0.ident().map(|ident| ident.value.clone());
+    let definitions : Vec<_> = nodes.iter().map_while(definition).collect();
+    let function = match definitions.first().and_then(name) {
+        Some(function) => function,
+        None => return vec![]
+    };
+    definitions.into_iter()
+        .take_while(|clause| name(clause).as_ref() == Some(&function))
+        .collect()
+}
+
+/// Variable a pattern binds its whole value to, if it does.
+fn variable(pattern : &Nodes) -> Option<&str> {
+    pattern.ident()
+        .filter(|ident| !ident.is_wildcard() && !ident.is_constructor())
+        .map(|ident| ident.value.as_str())
+}
+
+/// Checks if a pattern matches every value it can be given.
+fn is_irrefutable(pattern : &Nodes) -> bool {
+    match pattern {
+        Nodes::Ident(ident) => !ident.is_constructor(),
+        Nodes::Tuple(tuple) => tuple.elements.iter().all(is_irrefutable),
+        _ => false
+    }
+}
+
+/// Names of the sets that values may be cast to.
+const CAST_TYPES : &[&str] = &["Nat", "Int", "Rat", "Real"];
+
 fn append_unique<T : Clone + PartialEq>(v : &mut Vec<T>, e : T) -> usize {
     let index = v.iter().position(|c| c == &e);
     if index.is_none() { v.push(e); }
@@ -28,6 +105,9 @@ pub fn numerics_to_element<'a>(num : &ast::Numerics) -> Element<'a> {
     match num {
         ast::Numerics::Natural(n) => Element::ENatural(*n),
         ast::Numerics::Integer(n) => Element::EInteger(*n),
+        ast::Numerics::BigNatural(n) => Element::EBigNatural(n.clone()),
+        ast::Numerics::BigInteger(n) => Element::EBigInteger(n.clone()),
+        ast::Numerics::Rational(q) => Element::ERational(q.clone()),
         ast::Numerics::Real(n)    => Element::EReal(*n)
     }
 }
@@ -44,9 +124,15 @@ pub struct LocalBlock<'a> {
     pub globals : Vec<String>,
     pub operand_type : ast::StaticTypes,
     pub return_type  : ast::StaticTypes,
+    /// Issues found whilst compiling this block and its sub-blocks.
+    pub issues : Diagnostics,
 
     // Used only for compilation:
     pub locals_map : HashMap<String, u16>,
+    local_sites : HashMap<String, Site>,
+    /// Locals bound by earlier clauses of a function, or in `do' blocks
+    /// which have ended, which are not in scope of what is being compiled.
+    out_of_scope : HashSet<String>,
     types_to_check : VecDeque<IdentTypePair<'a>>,
     current_line  : usize,
     current_depth : usize,
@@ -73,8 +159,11 @@ impl<'a> LocalBlock<'a> {
             globals: vec![],
             operand_type: ast::StaticTypes::TUnknown,
             return_type:  ast::StaticTypes::TUnknown,
+            issues: Diagnostics::new(),
 
             locals_map: HashMap::new(),
+            local_sites: HashMap::new(),
+            out_of_scope: HashSet::new(),
             types_to_check: VecDeque::new(),
             current_line:  0,
             stack_depth:   0,
@@ -123,6 +212,11 @@ impl<'a> LocalBlock<'a> {
     fn push_operand(&mut self, i : u16) {
         let operand = Instr::Operand(i);
         self.instructions.push(operand);
+        // Line numbers do not touch the stack, so the constant last
+        //  pushed is not known to still be on top of it.
+        if self.last_instruction == Instr::Operator(Operators::SET_LINE as u8) {
+            return;
+        }
         self.change_stack_depth(
             self.last_instruction.depth_delta(
                 Some(operand)));
@@ -134,90 +228,82 @@ impl<'a> LocalBlock<'a> {
         index
     }
 
-    fn ident_assignment(&mut self, left : &'a ast::IdentNode, right : &'a Nodes) {
-        if self.types_to_check.is_empty() {
-            fatal!(TypeError, left.site.with_filename(&self.filename),
-                "You must state what set `{}' is a member of.
-                 No type-annotation found.", left.value)
-                    .print();
-        }
-        if self.locals_map.contains_key(&left.value) {
-            fatal!(CompError, left.site.with_filename(&self.filename),
+    /// Binds a new local, which must not already be bound, unless it has
+    /// gone out of scope, when its slot is used again.
+    fn bind(&mut self, ident : &ast::IdentNode) -> Fallible<u16> {
+        if let Some(&index) = self.locals_map.get(&ident.value) {
+            if self.out_of_scope.remove(&ident.value) {
+                self.local_sites.insert(ident.value.to_owned(), ident.site.clone());
+                return Ok(index);
+            }
+            let mut issue = issue!(CompError, V0023, ident.site.with_filename(&self.filename),
                 "Cannot mutate value of `{}',
-                 as it is already bound.", left.value)
-                    .print();
-        }
-        let index = self.insert_local(left.value.to_owned());
-
-        self.emit(right);
-        if left.static_type == ast::StaticTypes::TUnknown
-        || left.static_type != right.yield_type() {
-            self.push_operator(Operators::DUP);
-            let type_node = self.types_to_check.pop_front().unwrap().1;
-            self.emit(type_node);
-            self.push_operator(Operators::CHECK_TYPE);
-        } else {  // Otherwise just pop, type was already checked statically so
-                 //  its of no use to include in the compiled program,
-                //   as no dynamic checking is needed.
-            self.types_to_check.pop_front();
+                 as it is already bound.", ident.value);
+            if let Some(bound) = self.local_sites.get(&ident.value) {
+                issue = issue.label(bound.with_filename(&self.filename),
+                                    "first bound here");
+            }
+            return Err(issue);
         }
-        self.push_operator(Operators::STORE_LOCAL);
-        self.push_operand(index);
+        let index = self.insert_local(ident.value.to_owned());
+        self.local_sites.insert(ident.value.to_owned(), ident.site.clone());
+        Ok(index)
     }
 
-    fn function_assign(&mut self, left : &ast::CallNode, right : &'a Nodes) {
-        let mut arguments = left.collect();
-        let base_node = arguments.remove(0);
-
-        if let Nodes::Ident(ident) = base_node {
-            let name = format!("__{}_final", ident.value.to_owned());
-
-            let mut last_block = LocalBlock::new(&name, &self.filename);
-            // TODO: Be more careful here, not always an ident.
-            //  NEED TO DEAL WITH PATTERN MATCHING.
-            last_block.insert_local(arguments.last().unwrap().ident().unwrap().value.to_owned());
-            last_block.emit(right);
-            last_block.yield_last();
-
-            for i in (0..(arguments.len() - 1)).rev() {
-                let name = format!("__{}_{}", ident.value, i);
-                let mut super_block = LocalBlock::new(
-                    &name,
-                    &self.filename);
-                // Also TODO: Pattern matching, be careful in the future.
-                super_block.insert_local(arguments[i].ident().unwrap().value.to_owned());
-
-                let block_name = last_block.name.clone();
-                super_block.push_const_instr(Element::ECode(Box::new(last_block)));
-                super_block.push_const_instr(Element::ESymbol(Symbol::new(&block_name)));
-                super_block.push_operator(Operators::MAKE_FUNC);
-                super_block.yield_last();
-                last_block = super_block;
-            }
-
-            let index = self.insert_local(ident.value.to_owned());
-
-            self.push_const_instr(Element::ECode(Box::new(last_block)));
-            self.push_const_instr(Element::ESymbol(Symbol::new(&ident.value)));
-            self.push_operator(Operators::MAKE_FUNC);
-            self.push_operator(Operators::STORE_LOCAL);
+    /// Pushes the value of a variable, either local or of a superior block.
+    fn push_variable(&mut self, name : &str) {
+        if !self.locals_map.contains_key(name) || self.out_of_scope.contains(name) {
+            self.push_operator(Operators::PUSH_SUPER);
+            let index = append_unique(&mut self.globals, name.to_owned()) as u16;
             self.push_operand(index);
             return;
         }
+        self.push_operator(Operators::PUSH_LOCAL);
+        self.push_operand(self.locals_map[name]);
+    }
 
-        // A function of multiple arguments (say 3 f.eks),
-        //  must generate a function, which when called returns
-        //  a function, and when that function is called, it returns
-        //  the final value.
+    /// Stores the value on top of the stack in a local, which the
+    /// clauses of a function may each bind anew.
+    fn store_variable(&mut self, name : &str) {
+        let index = match self.locals_map.get(name) {
+            Some(index) => *index,
+            None => self.insert_local(name.to_owned())
+        };
+        self.out_of_scope.remove(name);
+        self.push_operator(Operators::STORE_LOCAL);
+        self.push_operand(index);
     }
 
-    fn annotation(&mut self, left : &ast::IdentNode, right : &'a Nodes) {
-        self.types_to_check.push_back(IdentTypePair(left.value.to_owned(), right));
+    /// Byte offset of the next instruction to be pushed.
+    fn byte_offset(&self) -> u16 {
+        self.instructions.iter()
+            .map(|instr| match instr {
+                Instr::Operator(_) => 1,
+                Instr::Operand(_)  => 2
+            })
+            .sum()
     }
 
-    fn emit(&mut self, node : &'a Nodes) {
-        let current_line = node.site().location.line.unwrap();
-        if self.current_line != current_line {
+    /// Pushes a jump to an instruction yet to be pushed, giving the
+    /// position of its operand, for `land' to fill in.
+    fn push_jump(&mut self, o : Operators) -> usize {
+        self.push_operator(o);
+        self.push_operand(0);
+        self.instructions.len() - 1
+    }
+
+    /// Points the jumps at the next instruction to be pushed.
+    fn land(&mut self, jumps : &[usize]) {
+        let offset = self.byte_offset();
+        for jump in jumps {
+            self.instructions[*jump] = Instr::Operand(offset);
+        }
+        // Having jumped, the constant last pushed may not be on the stack.
+        self.last_const_push_index = 0xffff;
+    }
+
+    fn set_line(&mut self, line : usize) {
+        if self.current_line != line {
             let len = self.instructions.len();
             if len > 1
             && self.instructions[len - 2]
@@ -225,24 +311,252 @@ impl<'a> LocalBlock<'a> {
                 self.instructions.pop();
                 self.instructions.pop();
             }
-            self.current_line = current_line;
+            self.current_line = line;
             self.push_operator(Operators::SET_LINE);
             self.push_operand(self.current_line as u16);
         }
+    }
 
-        match node {
-            Nodes::Ident(ident_node) => {
-                let s = &ident_node.value;
-                if !self.locals_map.contains_key(s) {
-                    self.push_operator(Operators::PUSH_SUPER);
-                    let index = append_unique(&mut self.globals, s.to_owned()) as u16;
-                    self.push_operand(index);
-                    return;
-                }
+    fn ident_assignment(&mut self, left : &'a ast::IdentNode, right : &'a Nodes) -> Fallible<()> {
+        let index = self.bind(left)?;
+
+        self.emit(right)?;
+        if let Some(type_node) = self.take_annotation(&left.value) {
+            if left.static_type == ast::StaticTypes::TUnknown
+            || left.static_type != right.yield_type() {
+                self.push_operator(Operators::DUP);
+                self.emit(type_node)?;
+                self.push_operator(Operators::CHECK_TYPE);
+            }  // Otherwise the type was already checked statically so
+              //  its of no use to include in the compiled program,
+             //   as no dynamic checking is needed.
+        } else {
+            // Carry on compiling, as if the value were of any type.
+            let site = left.site.with_filename(&self.filename);
+            let mut issue = issue!(TypeError, V0024, site,
+                "You must state what set `{}' is a member of.
+                 No type-annotation found.", left.value);
+            // Annotate with the set of the value, if it is a simple one.
+            match right.yield_type() {
+                value_type@StaticTypes::TNatural
+                | value_type@StaticTypes::TInteger
+                | value_type@StaticTypes::TRational
+                | value_type@StaticTypes::TReal
+                | value_type@StaticTypes::TString
+                | value_type@StaticTypes::TSymbol => {
+                    let set = StaticTypes::TSet(Box::new(value_type));
+                    issue = issue.fix(site.start(),
+                        &format!("{} : {}; ", left.value, set));
+                },
+                _ => ()
+            }
+            issue.report(&mut self.issues);
+        }
+        self.push_operator(Operators::STORE_LOCAL);
+        self.push_operand(index);
+        Ok(())
+    }
+
+    /// Compile a sub-block, moving its issues into this block.
+    fn emit_sub_block(&mut self, block : &mut LocalBlock<'a>, node : &'a Nodes) -> Fallible<()> {
+        let emitted = block.emit(node);
+        self.issues.append(&mut block.issues);
+        emitted
+    }
+
+    /// Compile an expression of the variables of a set-builder into `block'.
+    fn builder_block(&mut self, block : &mut LocalBlock<'a>, variables : &[&str],
+                     node : &'a Nodes) -> Fallible<()> {
+        for variable in variables {
+            block.insert_local(variable.to_string());
+        }
+        self.emit_sub_block(block, node)?;
+        block.yield_last();
+        Ok(())
+    }
 
-                self.push_operator(Operators::PUSH_LOCAL);
-                self.push_operand(self.locals_map[s]);
+    /// Compile the consecutive clauses of a function, e.g.
+    /// ```ignore
+    ///     f 1 _ = 2
+    ///     f _ 1 = 3
+    ///     f a b = a + b
+    /// ```
+    /// whose arguments are matched against the patterns of each clause
+    /// in turn, giving the body of the first clause to match them.
+    fn function_assign(&mut self, clauses : &[(&'a ast::CallNode, &'a Nodes)]) -> Fallible<()> {
+        let (left, _) = clauses[0];
+        self.set_line(left.site.location.line.unwrap());
+        let ident = match applied(left).0 {
+            Nodes::Ident(ident) => ident,
+            _ => return Ok(())
+        };
+        // Arguments every clause binds to the same variable are named
+        //  after it, and the rest are matched against by position.
+        let patterns : Vec<Vec<&Nodes>> = clauses.iter()
+            .map(|(left, _)| applied(left).1)
+            .collect();
+        let arity = patterns.iter().map(Vec::len).max().unwrap();
+        let parameters : Vec<String> = (0..arity).map(|i| {
+            let mut names = patterns.iter()
+                .map(|patterns| patterns.get(i).and_then(|pattern| variable(pattern)));
+            match names.next().flatten() {
+                Some(name) if names.all(|other| other == Some(name)) => name.to_owned(),
+                _ => format!("<argument {}>", i)
+            }
+        }).collect();
+
+        // Functions are only checked statically.
+        self.take_annotation(&ident.value);
+        let name = format!("__{}_final", ident.value);
+        let mut last_block = LocalBlock::new(&name, &self.filename);
+        last_block.insert_local(parameters[arity - 1].to_owned());
+        let compiled = last_block.clauses(&parameters, clauses);
+        self.issues.append(&mut last_block.issues);
+        compiled?;
+
+        for i in (0..(arity - 1)).rev() {
+            let name = format!("__{}_{}", ident.value, i);
+            let mut super_block = LocalBlock::new(
+                &name,
+                &self.filename);
+            super_block.insert_local(parameters[i].to_owned());
+
+            let block_name = last_block.name.clone();
+            super_block.push_const_instr(Element::ECode(Box::new(last_block)));
+            super_block.push_const_instr(Element::ESymbol(Symbol::new(&block_name)));
+            super_block.push_operator(Operators::MAKE_FUNC);
+            super_block.yield_last();
+            last_block = super_block;
+        }
+
+        let index = self.bind(ident)
+            .map_err(|issue| issue.note("Clauses of a function must follow one another."))?;
+
+        self.push_const_instr(Element::ECode(Box::new(last_block)));
+        self.push_const_instr(Element::ESymbol(Symbol::new(&ident.value)));
+        self.push_operator(Operators::MAKE_FUNC);
+        self.push_operator(Operators::STORE_LOCAL);
+        self.push_operand(index);
+        Ok(())
+    }
+
+    /// Compile the clauses of a function into its final block, which
+    /// has been given the last of its `parameters'.  Each clause jumps
+    /// to the next if its patterns do not match, and `NO_MATCH' fails
+    /// at runtime if none of them do.
+    fn clauses(&mut self, parameters : &[String],
+               clauses : &[(&'a ast::CallNode, &'a Nodes)]) -> Fallible<()> {
+        let last = parameters.last().unwrap();
+        // Whether the last clause matches any arguments it is given.
+        let mut exhaustive = false;
+        for (left, body) in clauses {
+            self.out_of_scope = self.locals_map.keys()
+                .filter(|local| *local != last)
+                .cloned()
+                .collect();
+            self.set_line(left.site.location.line.unwrap());
+
+            let patterns = applied(left).1;
+            let mut jumps = vec![];
+            for (pattern, parameter) in patterns.iter().zip(parameters) {
+                self.match_pattern(pattern, parameter, &mut jumps)?;
+            }
+            // A clause with fewer arguments gives a function of the rest.
+            for parameter in parameters[patterns.len()..].iter().rev() {
+                self.push_variable(parameter);
+            }
+            self.emit(body)?;
+            for _ in patterns.len()..parameters.len() {
+                self.push_operator(Operators::CALL_1);
+            }
+            self.yield_last();
+            self.land(&jumps);
+            exhaustive = patterns.iter().all(|pattern| is_irrefutable(pattern));
+        }
+        if !exhaustive {
+            self.push_operator(Operators::NO_MATCH);
+            self.yield_last();
+        }
+        Ok(())
+    }
+
+    /// Match the value of the variable `source' against a pattern, binding
+    /// the pattern's variables.  Jumps are added for where it may not match.
+    fn match_pattern(&mut self, pattern : &'a Nodes, source : &str,
+                     jumps : &mut Vec<usize>) -> Fallible<()> {
+        match pattern {
+            Nodes::Ident(ident) if ident.is_wildcard() => (),
+            Nodes::Ident(ident) if !ident.is_constructor() => {
+                if ident.value != source {
+                    self.push_variable(source);
+                    self.store_variable(&ident.value);
+                }
+            },
+            Nodes::Tuple(tuple) => {
+                let elements : Vec<&Nodes> = tuple.elements.iter().collect();
+                self.match_parts(&elements, source, jumps)?;
             },
+            // `UNAPPLY' gives the arguments the constructor was applied to,
+            //  as a tuple, or `:false' if the value was not constructed by it.
+            Nodes::Call(call) if call.base_call().ident()
+                                     .is_some_and(ast::IdentNode::is_constructor) => {
+                let (constructor, parts) = applied(call);
+                let arguments = format!("{}[..]", source);
+                self.push_variable(source);
+                self.emit(constructor)?;
+                self.push_operator(Operators::UNAPPLY);
+                self.push_operand(parts.len() as u16);
+                self.store_variable(&arguments);
+                self.push_variable(&arguments);
+                jumps.push(self.push_jump(Operators::JUMP_UNLESS));
+                self.match_parts(&parts, &arguments, jumps)?;
+            },
+            // Literals and constants are compared to the value.
+            _ => {
+                self.push_variable(source);
+                self.emit(pattern)?;
+                self.push_operator(Operators::EQUAL);
+                jumps.push(self.push_jump(Operators::JUMP_UNLESS));
+            }
+        }
+        Ok(())
+    }
+
+    /// Match each element of the tuple in the variable `source' against
+    /// the respective pattern of `parts'.
+    fn match_parts(&mut self, parts : &[&'a Nodes], source : &str,
+                   jumps : &mut Vec<usize>) -> Fallible<()> {
+        for (i, part) in parts.iter().enumerate() {
+            if part.ident().is_some_and(ast::IdentNode::is_wildcard) {
+                continue;
+            }
+            self.push_variable(source);
+            self.push_const_instr(Element::ENatural(i));
+            self.push_operator(Operators::INDEX);
+            let name = variable(part).map(str::to_owned)
+                .unwrap_or_else(|| format!("{}[{}]", source, i));
+            self.store_variable(&name);
+            self.match_pattern(part, &name, jumps)?;
+        }
+        Ok(())
+    }
+
+    fn annotation(&mut self, left : &ast::IdentNode, right : &'a Nodes) {
+        self.types_to_check.push_back(IdentTypePair(left.value.to_owned(), right));
+    }
+
+    /// Takes the earliest annotation of `name' yet to be checked.
+    fn take_annotation(&mut self, name : &str) -> Option<&'a Nodes> {
+        let index = self.types_to_check.iter()
+            .position(|IdentTypePair(annotated, _)| annotated == name)?;
+        self.types_to_check.remove(index).map(|IdentTypePair(_, type_node)| type_node)
+    }
+
+    fn emit(&mut self, node : &'a Nodes) -> Fallible<()> {
+        self.set_line(node.site().location.line.unwrap());
+
+        match node {
+            Nodes::Ident(ident_node) => self.push_variable(&ident_node.value),
             Nodes::Nil(_) => {
                 self.push_const_instr(Element::ENil);
             },
@@ -255,6 +569,83 @@ impl<'a> LocalBlock<'a> {
             Nodes::Sym(sym_node) => {
                 self.push_const_instr(Element::ESymbol(Symbol::new(&sym_node.value)));
             },
+            Nodes::Set(set_node) => {
+                // `MAKE_SET' collects as many elements as it is told
+                //  from the top of the stack.
+                for element in &set_node.elements {
+                    self.emit(element)?;
+                }
+                self.push_operator(Operators::MAKE_SET);
+                self.push_operand(set_node.elements.len() as u16);
+            },
+            Nodes::Vec(ast::VecNode { elements, .. })
+            | Nodes::Tuple(ast::TupleNode { elements, .. }) => {
+                // Tuples (and vectors) of constants are constants themselves,
+                //  otherwise `MAKE_TUPLE' collects the elements from the
+                //  top of the stack.
+                if let Some(constant) = constant_element(node) {
+                    self.push_const_instr(constant);
+                    return Ok(());
+                }
+                for element in elements {
+                    self.emit(element)?;
+                }
+                self.push_operator(Operators::MAKE_TUPLE);
+                self.push_operand(elements.len() as u16);
+            },
+            Nodes::Builder(builder_node) => {
+                // `MAKE_BUILDER' takes the set-builder's predicate and
+                //  image from the top of the stack, followed by the set
+                //  each of its variables is drawn from.
+                for generator in &builder_node.generators {
+                    self.emit(&generator.domain)?;
+                }
+                let variables : Vec<&str> = builder_node.generators.iter()
+                    .map(|generator| generator.variable.value.as_str())
+                    .collect();
+                let base_type = Some(builder_node.element_type.to_owned())
+                    .filter(|element_type| *element_type != StaticTypes::TUnknown);
+                let mut set = types::Set::new(&self.filename, base_type);
+                if let Some(condition) = &builder_node.condition {
+                    self.builder_block(&mut set.conditions, &variables, condition)?;
+                }
+                let is_variable = builder_node.element.ident()
+                    .is_some_and(|ident| variables == [ident.value.as_str()]);
+                if !is_variable {
+                    let mut image = LocalBlock::new("<set-image>", &self.filename);
+                    self.builder_block(&mut image, &variables, &builder_node.element)?;
+                    set.image = Some(image);
+                }
+                self.push_const_instr(Element::ESet(Box::new(set)));
+                self.push_operator(Operators::MAKE_BUILDER);
+                self.push_operand(builder_node.generators.len() as u16);
+            },
+            Nodes::Interp(interp_node) => {
+                // `CONCAT' takes its left operand from the top of the stack,
+                //  so the string is concatenated starting from its end.
+                for (i, segment) in interp_node.segments.iter().rev().enumerate() {
+                    self.emit(segment)?;
+                    let cast_from : u16 = match segment.yield_type() {
+                        StaticTypes::TString  => 0,
+                        StaticTypes::TRational => 0b0000_1000,
+                        StaticTypes::TReal    => 0b0000_0011,
+                        StaticTypes::TInteger => 0b0000_0010,
+                        StaticTypes::TNatural => 0b0000_0001,
+                        _ => return Err(issue!(CompError, V0034,
+                            segment.site().with_filename(&self.filename),
+                            "Cannot interpolate a value of type `{}' into a string.",
+                            segment.yield_type()))
+                    };
+                    if cast_from != 0 {
+                        self.push_operator(Operators::CAST);
+                        self.push_operand(cast_from << 8 | 0b0000_0100);
+                    }
+                    if i > 0 {
+                        self.push_operator(Operators::CONCAT);
+                    }
+                }
+            },
+            Nodes::Block(block_node) => self.do_block(block_node)?,
             Nodes::Call(call_node) => {
                 if let Nodes::Ident(ident_node) = &*call_node.callee {
                     let mut do_return = true;
@@ -267,19 +658,20 @@ impl<'a> LocalBlock<'a> {
                                 StaticTypes::TInteger => 0x02,
                                 StaticTypes::TReal    => 0x03,
                                 StaticTypes::TString  => 0x04,
-                                _ => fatal!(CompError, arg.site().with_filename(&self.filename),
+                                StaticTypes::TRational => 0x08,
+                                _ => return Err(issue!(CompError, V0025,
+                                        arg.site().with_filename(&self.filename),
                                         "__raw_print cannot display `{}' types.",
-                                        arg.yield_type())
-                                            .crash_and_burn()
+                                        arg.yield_type()))
                             };
 
-                            self.emit(arg);
+                            self.emit(arg)?;
                             self.push_operator(Operators::RAW_PRINT);
                             self.push_operand(print_type);
                         }
                         _ => do_return = false
                     };
-                    if do_return { return; }
+                    if do_return { return Ok(()); }
                 }
                 if call_node.is_binary() {
                     let ident = call_node.callee.call().unwrap().callee.ident().unwrap();
@@ -290,49 +682,57 @@ impl<'a> LocalBlock<'a> {
 
                     // Check for cast.
                     if ident.value == "cast" {
-                        self.emit(args[0]);
+                        self.emit(args[0])?;
                         self.push_operator(Operators::CAST);
 
                         if let Some(cast_name) = args[1].get_name() {
                             let cast_to : u16 = match cast_name {
+                                "Rat"  => 0b0000_1000,
                                 "Real" => 0b0000_0011,
                                 "Int"  => 0b0000_0010,
                                 "Nat"  => 0b0000_0001,
-                                _ => fatal!(TypeError,
-                                    args[1].site().with_filename(&self.filename),
-                                    "Compiler does not know how to cast to `{}'.",
-                                    cast_name)
-                                        .crash_and_burn()
+                                _ => {
+                                    let issue = issue!(TypeError, V0026,
+                                        args[1].site().with_filename(&self.filename),
+                                        "Compiler does not know how to cast to `{}'.",
+                                        cast_name);
+                                    let note = suggest::did_you_mean(cast_name,
+                                        CAST_TYPES.iter().copied());
+                                    return Err(match note {
+                                        Some(note) => issue.note(&note),
+                                        None => issue
+                                    });
+                                }
                             };
                             let cast_from = match args[0].yield_type() {
+                                ast::StaticTypes::TRational => 0b0000_1000,
                                 ast::StaticTypes::TReal    => 0b0000_0011,
                                 ast::StaticTypes::TInteger => 0b0000_0010,
                                 ast::StaticTypes::TNatural => 0b0000_0001,
-                                _ => fatal!(TypeError,
+                                _ => return Err(issue!(TypeError, V0027,
                                     args[0].site().with_filename(&self.filename),
                                     "Compiler does not know how to cast from `{}'.",
-                                    args[0].yield_type())
-                                        .crash_and_burn()
+                                    args[0].yield_type()))
                             };
                             self.push_operand(cast_from << 8 | cast_to);
                         } else {
-                            issue!(CompError,
+                            issue!(CompError, V0028,
                                 args[1].site().with_filename(&self.filename),
                                 "Cast-type provided to `cast' has to be a type-name.")
-                                    .print();
+                                    .report(&mut self.issues);
                         }
-                        return;
+                        return Ok(());
                     }
 
                     // Check for assignment.
                     if ident.value == "=" {
                         // Direct variable assignment:
                         if let Nodes::Ident(left) = args[0] {
-                            self.ident_assignment(left, args[1]);
+                            self.ident_assignment(left, args[1])?;
                         } else if let Nodes::Call(left) = args[0] {
-                            self.function_assign(left, args[1]);
+                            self.function_assign(&[(left, args[1])])?;
                         }
-                        return;
+                        return Ok(());
                     }
 
                     // Check for type annotation.
@@ -340,37 +740,95 @@ impl<'a> LocalBlock<'a> {
                         // If the LHS is not an ident, it is not a
                         //   valid annotation.
                         if args[0].ident().is_none() {
-                            issue!(CompError,
+                            issue!(CompError, V0006,
                                 args[0].site().with_filename(&self.filename),
                                 "Left of `:` type annotator must be an identifier.")
-                                    .print();
+                                    .report(&mut self.issues);
+                            return Ok(());
                         }
                         let left = args[0].ident().unwrap();
 
                         // Annotation of variable or function.
                         self.annotation(left, args[1]);
-                        return;
+                        return Ok(());
+                    }
+
+                    // `INDEX' takes the index from the top of the
+                    //  stack, and the tuple below it.
+                    if ident.value == "index" {
+                        self.emit(args[1])?;
+                        self.emit(args[0])?;
+                        self.push_operator(Operators::INDEX);
+                        return Ok(());
                     }
 
                     // Check for fast internal binary operations such as +, -, *, /, etc.
                     let maybe_op = internal_functions::get_internal_op(&ident.value, Some(&args));
-                    if let Some(op) = maybe_op {
-                    if let Instr::Operator(operator) = op {
-                        self.emit(args[1]);
-                        self.emit(args[0]);
+                    if let Some(Instr::Operator(operator)) = maybe_op {
+                        self.emit(args[1])?;
+                        self.emit(args[0])?;
                         self.push_operator(Operators::from_u8(operator).unwrap());
-                        return;
-                    }}
+                        return Ok(());
+                    }
                 }
                 // TODO: Optimise to implicitly ignore currying and use CALL_N instead.
                 //  Also, check that we are indeed calling a function, and not anything else
                 //  by checking the static yield type.
-                self.emit(&call_node.operands[0]);
-                self.emit(&*call_node.callee);
+                self.emit(&call_node.operands[0])?;
+                self.emit(&*call_node.callee)?;
                 self.push_operator(Operators::CALL_1);
             },
             _ => ()
         };
+        Ok(())
+    }
+
+    /// Compile the statements of a `do' block in turn, keeping only the
+    /// value of the last of them on the stack, or nil if it gives none.
+    /// Variables bound in the block go out of scope at its end.
+    fn do_block(&mut self, block : &'a ast::BlockNode) -> Fallible<()> {
+        if block.keyword != "do" {
+            return Err(issue!(CompError, V0048,
+                block.site.with_filename(&self.filename),
+                "Blocks opened by `{}:' cannot be compiled yet.", block.keyword)
+                .note("Only `do:' blocks are supported."));
+        }
+        let in_scope : HashSet<String> = self.locals_map.keys()
+            .filter(|local| !self.out_of_scope.contains(*local))
+            .cloned()
+            .collect();
+        let depth = self.current_depth;
+        let mut i = 0;
+        while i < block.statements.len() {
+            let (count, emitted) = self.statement(&block.statements[i..]);
+            emitted?;
+            i += count;
+            let kept = usize::from(i == block.statements.len());
+            while self.current_depth > depth + kept {
+                self.push_operator(Operators::POP);
+            }
+        }
+        if self.current_depth == depth {
+            self.push_const_instr(Element::ENil);
+        }
+        let bound : Vec<String> = self.locals_map.keys()
+            .filter(|local| !in_scope.contains(*local))
+            .cloned()
+            .collect();
+        self.out_of_scope.extend(bound);
+        Ok(())
+    }
+
+    /// Compile the statement at the start of `nodes', or the clauses of a
+    /// function starting there, giving how many of the nodes were compiled.
+    fn statement(&mut self, nodes : &'a [Nodes]) -> (usize, Fallible<()>) {
+        // Consecutive clauses of a function define it together.
+        let clauses = clauses(nodes);
+        if clauses.is_empty() {
+            (1, self.emit(&nodes[0]))
+        } else {
+            (clauses.len(), self.function_assign(&clauses))
+        }
     }
 
     fn yield_last(&mut self) {
@@ -380,9 +838,16 @@ impl<'a> LocalBlock<'a> {
         self.push_operator(Operators::YIELD);
     }
 
+    /// Compile every node into this block.  A node that cannot be
+    /// compiled is reported, and compilation moves on to the next one.
     pub fn generate(&mut self, nodes : &'a [Nodes]) {
-        for node in nodes {
-            self.emit(node);
+        let mut i = 0;
+        while i < nodes.len() {
+            let (count, emitted) = self.statement(&nodes[i..]);
+            i += count;
+            if let Err(issue) = emitted {
+                issue.report(&mut self.issues);
+            }
         }
         self.yield_last();
     }
@@ -395,6 +860,12 @@ impl<'a> fmt::Display for LocalBlock<'a> {
             if let Element::ECode(local_block_box) = c {
                 write!(f, "{}", *local_block_box)?;
             }
+            if let Element::ESet(set) = c {
+                write!(f, "{}", set.conditions)?;
+                if let Some(image) = &set.image {
+                    write!(f, "{}", image)?;
+                }
+            }
         }
         write!(f, "\n{}:", self.name)?;
         writeln!(f,"
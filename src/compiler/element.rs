@@ -5,6 +5,9 @@ use std::hash::{Hash, Hasher};
 #[cfg(feature="debug")]
 use snailquote::escape;
 
+use num_bigint::{BigInt, BigUint};
+use num_rational::BigRational;
+
 use super::block;
 use super::types;
 
@@ -27,11 +30,23 @@ impl Symbol {
             string: s.to_owned()
         }
     }
+
+    /// Name of the symbol, without the colon in front.
+    pub fn name(&self) -> &str {
+        &self.string
+    }
 }
 
+/// Symbols which could not be written bare are quoted, e.g. `:"a b"'.
 impl fmt::Display for Symbol {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, ":{}", self.string)
+        let bare = !self.string.is_empty()
+            && !self.string.contains(|c : char| c.is_whitespace() || c == '"');
+        if bare {
+            write!(f, ":{}", self.string)
+        } else {
+            write!(f, ":{:?}", self.string)
+        }
     }
 }
 
@@ -45,11 +60,18 @@ impl PartialEq for Symbol {
 pub enum Element<'a> {
     ENatural(usize),
     EInteger(isize),
+    /// Naturals and integers too large for a pointer-sized number.
+    EBigNatural(BigUint),
+    EBigInteger(BigInt),
+    ERational(BigRational),
     EReal(f64),
     EString(&'a str),
     ESymbol(Symbol),
     ECode(Box<block::LocalBlock<'a>>),
     ESet(Box<types::Set<'a>>),
+    /// Tuple of constants, e.g. `(1, "a")', or a vector of
+    /// constants, as vectors are tuples of elements of one type.
+    ETuple(Vec<Element<'a>>),
     ENil
 }
 
@@ -58,6 +80,9 @@ impl<'a> Element<'a> {
         match *self {
             Element::ENatural(_)
             | Element::EInteger(_)
+            | Element::EBigNatural(_)
+            | Element::EBigInteger(_)
+            | Element::ERational(_)
             | Element::EReal(_) => true,
             _ => false
         }
@@ -71,11 +96,15 @@ impl<'a> fmt::Display for Element<'a> {
         let s = match self {
             Element::ENatural(t) => format!("{: <13} (Nat) ", t),
             Element::EInteger(t) => format!("{: <13} (Int) ", t),
+            Element::EBigNatural(t) => format!("{: <13} (Nat) ", t),
+            Element::EBigInteger(t) => format!("{: <13} (Int) ", t),
+            Element::ERational(t) => format!("{: <13} (Rat) ", t),
             Element::EReal(t)    => format!("{: <13} (Real)", if t.fract() == 0f64 { format!("{:.1}", t) } else { f64::to_string(t) }),
             Element::EString(t)  => format!("{: <13} (Str) ", format!("\"{}\"", escape(t))),
             Element::ESymbol(t)  => format!("{: <13} (Sym) ", t.to_string()),
             Element::ECode(t)    => format!("{: <13} (Code)", t.name),
             Element::ESet(t)     => format!("{: <13p} (Set) ", t),
+            Element::ETuple(t)   => format!("{: <13} (Tup) ", format!("{} elements", t.len())),
             Element::ENil        => format!("{: <13}(Empty) ", "()"),
         };
         write!(f, "{}", s)
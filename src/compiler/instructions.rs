@@ -26,13 +26,18 @@ impl Instr {
                     Operators::DUP_N       => operand as isize,
                     Operators::CAST        =>  0,
                     Operators::RAW_PRINT   =>  0,
+                    Operators::MAKE_SET    =>  1 - operand as isize,
+                    Operators::MAKE_BUILDER => -(operand as isize),
+                    Operators::MAKE_TUPLE  =>  1 - operand as isize,
+                    Operators::UNAPPLY     => -1,
+                    Operators::JUMP_UNLESS => -1,
                     Operators::SET_LINE    =>  0,
                     _ => panic!("This type of opcode doesn't take operands.")
                 };
             }}
         } else if let Instr::Operator(code) = self {
             match code {
-                40..=56 => return -1,
+                40..=60 => return -1,
                 _ => ()
             }
             return match Operators::from_u8(code.to_owned()).unwrap() {
@@ -42,6 +47,9 @@ impl Instr {
                 Operators::CALL_1 => -1,
                 Operators::CHECK_TYPE => -2,
                 Operators::MAKE_FUNC  => -1,
+                Operators::INDEX      => -1,
+                Operators::EQUAL      => -1,
+                Operators::NO_MATCH   =>  0,
                 Operators::YIELD      => -1,
                 Operators::NOP => 0,
                 _ => panic!("This opcode must take an operand.")
@@ -88,6 +96,14 @@ pub enum Operators {
     MAKE_FUNC   = 12,  // TAKES 0 OPERAND(s)
     YIELD       = 13,  // TAKES 0 OPERAND(s)
     RAW_PRINT   = 14,  // TAKES 1 OPERAND(s)
+    MAKE_SET    = 15,  // TAKES 1 OPERAND(s) (Number of elements)
+    MAKE_BUILDER = 16, // TAKES 1 OPERAND(s) (Number of variables)
+    MAKE_TUPLE  = 17,  // TAKES 1 OPERAND(s) (Number of elements)
+    INDEX       = 18,  // TAKES 0 OPERAND(s)
+    EQUAL       = 19,  // TAKES 0 OPERAND(s)
+    UNAPPLY     = 20,  // TAKES 1 OPERAND(s) (Number of arguments)
+    JUMP_UNLESS = 21,  // TAKES 1 OPERAND(s) (Byte offset of instruction)
+    NO_MATCH    = 22,  // TAKES 0 OPERAND(s)
 
     N_ADD       = 40,  // TAKES 0 OPERAND(s)
     I_ADD       = 41,  // TAKES 0 OPERAND(s)
@@ -106,6 +122,10 @@ pub enum Operators {
     I_DIV       = 54,  // TAKES 0 OPERAND(s)
     R_DIV       = 55,  // TAKES 0 OPERAND(s)
     U_DIV       = 56,  // TAKES 0 OPERAND(s)
+    Q_ADD       = 57,  // TAKES 0 OPERAND(s)
+    Q_SUB       = 58,  // TAKES 0 OPERAND(s)
+    Q_MUL       = 59,  // TAKES 0 OPERAND(s)
+    Q_DIV       = 60,  // TAKES 0 OPERAND(s)
 
     HALT        = 200, // TAKES 1 OPERAND(s)
 
@@ -125,6 +145,11 @@ impl Operators {
             | Self::DUP_N
             | Self::CAST
             | Self::RAW_PRINT
+            | Self::MAKE_SET
+            | Self::MAKE_BUILDER
+            | Self::MAKE_TUPLE
+            | Self::UNAPPLY
+            | Self::JUMP_UNLESS
             | Self::SET_LINE => true,
             _ => false
         }
@@ -151,6 +176,14 @@ impl fmt::Display for Operators {
             Operators::MAKE_FUNC   => "MAKE_FUNC\n",
             Operators::YIELD       => "YIELD\n",
             Operators::RAW_PRINT   => "RAW_PRINT",
+            Operators::MAKE_SET    => "MAKE_SET",
+            Operators::MAKE_BUILDER => "MAKE_BUILDER",
+            Operators::MAKE_TUPLE  => "MAKE_TUPLE",
+            Operators::INDEX       => "INDEX\n",
+            Operators::EQUAL       => "EQUAL\n",
+            Operators::UNAPPLY     => "UNAPPLY",
+            Operators::JUMP_UNLESS => "JUMP_UNLESS",
+            Operators::NO_MATCH    => "NO_MATCH\n",
 
             Operators::N_ADD       => "N_ADD\n",
             Operators::I_ADD       => "I_ADD\n",
@@ -173,6 +206,11 @@ impl fmt::Display for Operators {
             Operators::R_DIV       => "R_DIV\n",
             Operators::U_DIV       => "U_DIV\n",
 
+            Operators::Q_ADD       => "Q_ADD\n",
+            Operators::Q_SUB       => "Q_SUB\n",
+            Operators::Q_MUL       => "Q_MUL\n",
+            Operators::Q_DIV       => "Q_DIV\n",
+
             Operators::SET_LINE    => "SET_LINE",
 
             _ => "INVALID_OPCODE\n"
@@ -25,6 +25,7 @@ pub fn get_internal_op(ident : &str, args : Option<&Vec<&ast::Nodes>>) -> Option
             Some(Instr::Operator(match first {
                 ast::StaticTypes::TNatural => Operators::N_ADD,
                 ast::StaticTypes::TInteger => Operators::I_ADD,
+                ast::StaticTypes::TRational => Operators::Q_ADD,
                 ast::StaticTypes::TReal    => Operators::R_ADD,
                 _                          => Operators::U_ADD
             } as u8))
@@ -35,6 +36,7 @@ pub fn get_internal_op(ident : &str, args : Option<&Vec<&ast::Nodes>>) -> Option
             Some(Instr::Operator(match first {
                 ast::StaticTypes::TNatural => Operators::N_SUB,
                 ast::StaticTypes::TInteger => Operators::I_SUB,
+                ast::StaticTypes::TRational => Operators::Q_SUB,
                 ast::StaticTypes::TReal    => Operators::R_SUB,
                 _                          => Operators::U_SUB
             } as u8))
@@ -45,6 +47,7 @@ pub fn get_internal_op(ident : &str, args : Option<&Vec<&ast::Nodes>>) -> Option
             Some(Instr::Operator(match first {
                 ast::StaticTypes::TNatural => Operators::N_MUL,
                 ast::StaticTypes::TInteger => Operators::I_MUL,
+                ast::StaticTypes::TRational => Operators::Q_MUL,
                 ast::StaticTypes::TReal    => Operators::R_MUL,
                 _                          => Operators::U_MUL
             } as u8))
@@ -52,12 +55,23 @@ pub fn get_internal_op(ident : &str, args : Option<&Vec<&ast::Nodes>>) -> Option
         "/" => {
             if is_uni { return Some(Instr::Operator(Operators::U_DIV as u8)); }
 
+            // Naturals and integers are cast to rationals to be divided.
             Some(Instr::Operator(match first {
-                ast::StaticTypes::TNatural => Operators::N_DIV,
-                ast::StaticTypes::TInteger => Operators::I_DIV,
+                ast::StaticTypes::TRational => Operators::Q_DIV,
                 ast::StaticTypes::TReal    => Operators::R_DIV,
                 _                          => Operators::U_DIV
             } as u8))
+        },
+        "div" => {
+            if is_uni { return None; }
+
+            // Truncating division of naturals and integers, that of
+            //  other numbers is left to a function call.
+            match first {
+                ast::StaticTypes::TNatural => Some(Instr::Operator(Operators::N_DIV as u8)),
+                ast::StaticTypes::TInteger => Some(Instr::Operator(Operators::I_DIV as u8)),
+                _ => None
+            }
         }
         _ => None
     }
@@ -34,6 +34,8 @@ use super::block;
 use element::Element;
 use instructions::Instr;
 
+use crate::syntax::ast::StaticTypes;
+
 
 /// Gives each type a specifier prefix to identify them.
 fn constant_ident_prefix(element : &Element) -> u8 {
@@ -43,10 +45,31 @@ fn constant_ident_prefix(element : &Element) -> u8 {
         Element::EInteger(_) => 0x02,
         Element::EReal(_)    => 0x03,
         Element::EString(_)  => 0x04,
-        _ => panic!("No byte-ident for this constant type")
+        Element::ESymbol(_)  => 0x05,
+        Element::EBigNatural(_) => 0x06,
+        Element::EBigInteger(_) => 0x07,
+        Element::ERational(_)   => 0x08,
+        Element::ESet(_)        => 0x09,
+        Element::ETuple(_)      => 0x0a,
+        Element::ECode(_)       => 0x0b,
     } as u8;
 }
 
+/// Specifies the type of the members of a set, by the prefix of
+/// constants of that type, or `0x00' if it is not a simple type.
+fn type_specifier(static_type : Option<&StaticTypes>) -> u8 {
+    match static_type {
+        Some(StaticTypes::TNatural)  => 0x01,
+        Some(StaticTypes::TInteger)  => 0x02,
+        Some(StaticTypes::TReal)     => 0x03,
+        Some(StaticTypes::TString)   => 0x04,
+        Some(StaticTypes::TSymbol)   => 0x05,
+        Some(StaticTypes::TRational) => 0x08,
+        Some(StaticTypes::TNil)      => 0xff,
+        _ => 0x00
+    }
+}
+
 macro_rules! num_marshal_append {
     ($num:expr, $arr:expr) => {
         {
@@ -79,6 +102,30 @@ macro_rules! num_marshal_append {
  *         /￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣￣\
  *  `[TPS] [NUM OF SIZE BYTES (n)] [SIZE BYTE 1]...[SIZE BYTE n] [CHAR 1]...[CHAR m]`
  *                                 \_____size of string (m)____/
+ *
+ * For big naturals and integers (those which do not fit in 64 bits):
+ *  `[TPS] [NUM OF SIZE BYTES (n)] [SIZE BYTE 1]...[SIZE BYTE n] [BYTE 1]...[BYTE m]`
+ *  where the m bytes are the big-endian magnitude for naturals, and
+ *  the big-endian two's complement for integers, i.e. they are
+ *  laid out as strings are.
+ *
+ * For rationals:
+ *  `[TPS] [NUMERATOR] [DENOMINATOR]`
+ *  where the numerator is laid out as a big integer, and the (positive)
+ *  denominator as a big natural, both without their prefixes.
+ *
+ * For tuples (and vectors, which are tuples of elements of one type):
+ *  `[TPS] [NUM OF SIZE BYTES (n)] [SIZE BYTE 1]...[SIZE BYTE n] [ELEMENT 1]...[ELEMENT m]`
+ *  where each of the m elements is a marshalled constant, with its prefix.
+ *
+ * For sets made by set-builders:
+ *  `[TPS] [MEMBER TYPE] [CONDITIONS BLOCK] [HAS IMAGE] ([IMAGE BLOCK])`
+ *  where the member type is given by a constant's prefix (or 0x00),
+ *  the blocks are marshalled code blocks, and the image block is only
+ *  present if the byte before it is 0x01.
+ *
+ * For code blocks (e.g. the body of a function):
+ *  `[TPS] [CODE BLOCK]`
  */
 fn marshal_element(element : &Element) -> Vec<u8> {
     let mut bytes : Vec<u8> = vec![];
@@ -98,22 +145,64 @@ fn marshal_element(element : &Element) -> Vec<u8> {
             bytes.push(constant_ident_prefix(element));
             num_marshal_append!(r, bytes);
         },
+        Element::EBigNatural(n) => {
+            let digits = n.to_bytes_be();
+            bytes.push(constant_ident_prefix(element));
+            num_marshal_append!(digits.len(), bytes);
+            bytes.extend(digits);
+        },
+        Element::EBigInteger(i) => {
+            let digits = i.to_signed_bytes_be();
+            bytes.push(constant_ident_prefix(element));
+            num_marshal_append!(digits.len(), bytes);
+            bytes.extend(digits);
+        },
+        Element::ERational(q) => {
+            let numerator = q.numer().to_signed_bytes_be();
+            let denominator = q.denom().magnitude().to_bytes_be();
+            bytes.push(constant_ident_prefix(element));
+            num_marshal_append!(numerator.len(), bytes);
+            bytes.extend(numerator);
+            num_marshal_append!(denominator.len(), bytes);
+            bytes.extend(denominator);
+        },
         Element::EString(s) => {
             let s_bytes = s.as_bytes().to_vec();
             let s_bytes_len = s.len();
             bytes.push(constant_ident_prefix(element));
             num_marshal_append!(s_bytes_len, bytes);
             bytes.extend(s_bytes);
-        }
-
-        #[allow(unreachable_code)]
-        _ => {
-            #[cfg(feature="debug")] {
-                panic!("I do not know how to marshal type of `{}'.",
-                    element);
+        },
+        // Symbols are marshalled by name, like strings.
+        Element::ESymbol(symbol) => {
+            let name = symbol.name().as_bytes();
+            bytes.push(constant_ident_prefix(element));
+            num_marshal_append!(name.len(), bytes);
+            bytes.extend(name);
+        },
+        Element::ETuple(elements) => {
+            bytes.push(constant_ident_prefix(element));
+            num_marshal_append!(elements.len(), bytes);
+            for element in elements {
+                bytes.extend(marshal_element(element));
             }
-            panic!("Type cannot be marshalled! This is a (big) bug!")
-        }
+        },
+        Element::ECode(block) => {
+            bytes.push(constant_ident_prefix(element));
+            bytes.extend(marshal_block(block));
+        },
+        Element::ESet(set) => {
+            bytes.push(constant_ident_prefix(element));
+            bytes.push(type_specifier(set.base_type()));
+            bytes.extend(marshal_block(&set.conditions));
+            match &set.image {
+                Some(image) => {
+                    bytes.push(0x01);
+                    bytes.extend(marshal_block(image));
+                },
+                None => bytes.push(0x00)
+            }
+        },
     };
     bytes
 }
@@ -23,25 +23,39 @@ pub struct Set<'a> {
     unions : Vec<Set<'a>>,
     intersections : Vec<Set<'a>>,
     difference : Vec<Set<'a>>,
-    conditons : block::LocalBlock<'a>
+    /// Predicate on the variables of a set-builder, yielding `:true'
+    /// for those giving elements.  Empty if there is no predicate.
+    pub conditions : block::LocalBlock<'a>,
+    /// Gives each element from the variables of a set-builder, unless
+    /// its elements are the values of its only variable.
+    pub image : Option<block::LocalBlock<'a>>,
 }
 
 impl<'a> Set<'a> {
-    pub fn new(filename : &'a str, base_type : Option<ast::StaticTypes>) -> Self {
+    pub fn new(filename : &str, base_type : Option<ast::StaticTypes>) -> Self {
         Self {
             base_type,
             elements: vec![],
             unions: vec![],
             intersections: vec![],
             difference: vec![],
-            conditons : block::LocalBlock::new("<set-conditions>", filename)
+            conditions: block::LocalBlock::new("<set-conditions>", filename),
+            image: None,
         }
     }
+
+    pub fn base_type(&self) -> Option<&ast::StaticTypes> {
+        self.base_type.as_ref()
+    }
+
     pub fn is_memeber(&self, e : Element) -> bool {
         if let Some(base) = &self.base_type {
             return match base {
-                ast::StaticTypes::TNatural => is_elem!(e, Element::ENatural),
-                ast::StaticTypes::TInteger => is_elem!(e, Element::EInteger),
+                ast::StaticTypes::TNatural => is_elem!(e, Element::ENatural)
+                    || is_elem!(e, Element::EBigNatural),
+                ast::StaticTypes::TInteger => is_elem!(e, Element::EInteger)
+                    || is_elem!(e, Element::EBigInteger),
+                ast::StaticTypes::TRational => is_elem!(e, Element::ERational),
                 ast::StaticTypes::TReal    => is_elem!(e, Element::EReal),
                 ast::StaticTypes::TSymbol  => is_elem!(e, Element::ESymbol),
                 ast::StaticTypes::TString  => is_elem!(e, Element::EString),
@@ -0,0 +1,108 @@
+use crate::issue::{Diagnostics, Edit};
+use crate::source::{SourceId, SourceMap};
+
+/// Whether the bytes an edit replaces are in the source, and start on
+/// the line of its site, i.e. it was not made for another version of it.
+fn matches(source : &str, edit : &Edit, start : usize, end : usize) -> bool {
+    let in_source = end <= source.len()
+        && source.is_char_boundary(start)
+        && source.is_char_boundary(end);
+    in_source && edit.site.location.line.is_none_or(|line|
+        source[..start].matches('\n').count() + 1 == line)
+}
+
+/// Apply edits to a piece of source code, returning the new source and
+/// the number of edits applied.  Edits overlapping an edit earlier in the
+/// source, or not matching the source, are skipped.
+pub fn apply(source : &str, edits : &[&Edit]) -> (String, usize) {
+    let mut ranges : Vec<(usize, usize, &str)> = edits.iter()
+        .filter_map(|edit| {
+            let (start, end) = edit.range()?;
+            if matches(source, edit, start, end) {
+                Some((start, end, edit.replacement.as_str()))
+            } else { None }
+        })
+        .collect();
+    ranges.sort_by_key(|&(start, end, _)| (start, end));
+    // The same repair may be suggested by more than one issue.
+    ranges.dedup();
+
+    let mut fixed = String::with_capacity(source.len());
+    let mut applied = 0;
+    let mut last_end = 0;
+    for (start, end, replacement) in ranges {
+        if start < last_end {
+            continue;
+        }
+        fixed.push_str(&source[last_end..start]);
+        fixed.push_str(replacement);
+        last_end = end;
+        applied += 1;
+    }
+    fixed.push_str(&source[last_end..]);
+    (fixed, applied)
+}
+
+/// Fixed contents of every source that has issues with edits,
+/// along with how many edits were applied to it.
+pub fn fix_sources(issues : &Diagnostics, sources : &SourceMap)
+    -> Vec<(SourceId, String, usize)> {
+    let mut fixed = vec![];
+    let mut ids : Vec<SourceId> = issues.iter()
+        .flat_map(|issue| issue.edits.iter())
+        .filter_map(|edit| SourceId::of(&edit.site))
+        .collect();
+    ids.dedup();
+
+    for id in ids {
+        if fixed.iter().any(|(done, _, _)| *done == id) {
+            continue;
+        }
+        if let Some(source) = sources.get(&id) {
+            let edits : Vec<&Edit> = issues.iter()
+                .flat_map(|issue| issue.edits.iter())
+                .filter(|edit| SourceId::of(&edit.site).as_ref() == Some(&id))
+                .collect();
+            let (content, applied) = apply(&source.content, &edits);
+            fixed.push((id, content, applied));
+        }
+    }
+    fixed
+}
+
+#[cfg(test)]
+mod applying {
+    use super::*;
+    use crate::site::Site;
+
+    fn edit(offset : usize, span : usize, replacement : &str) -> Edit {
+        Edit {
+            site: Site::single_line(1, offset + 1, span, span, offset),
+            replacement: replacement.to_owned()
+        }
+    }
+
+    #[test]
+    fn edits_are_applied_in_order() {
+        let edits = [edit(10, 0, ")"), edit(0, 0, "a : Nat; "), edit(4, 1, "(")];
+        let edits : Vec<&Edit> = edits.iter().collect();
+        assert_eq!(apply("a = [1 + 2", &edits), (String::from("a : Nat; a = (1 + 2)"), 3));
+    }
+
+    #[test]
+    fn overlapping_edits_are_skipped() {
+        let edits = [edit(4, 2, ":\")\""), edit(5, 1, "]"), edit(4, 2, ":\")\"")];
+        let edits : Vec<&Edit> = edits.iter().collect();
+        assert_eq!(apply("x = :)", &edits), (String::from("x = :\")\""), 1));
+    }
+
+    #[test]
+    fn mismatched_edits_are_skipped() {
+        let mut moved = edit(4, 2, ":\")\"");
+        moved.site.location.line = Some(2);
+        let edits = [edit(40, 0, ")"), edit(5, 0, "!"), moved];
+        let edits : Vec<&Edit> = edits.iter().collect();
+        // Past the end, inside of a character, and on another line.
+        assert_eq!(apply("x = é)", &edits), (String::from("x = é)"), 0));
+    }
+}
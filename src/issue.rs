@@ -1,18 +1,18 @@
 #![allow(non_camel_case_types)]
 #![allow(clippy::pub_enum_variant_names)]
 
-use crate::site::{Site, Location};
+use crate::site::Site;
+use crate::codes::Code;
+use crate::source::{SourceFile, SourceId, SourceMap};
 
-use std::fs;
 use std::fmt;
-use std::io::{BufRead, BufReader};
 
 use colored;
 use colored::*;
 
 use unindent::unindent;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Kind {
       LexError,   LexWarn,
     ParseError, ParseWarn,
@@ -20,15 +20,65 @@ pub enum Kind {
      CompError,  CompWarn
 }
 
+/// Secondary site attached to an issue, e.g. where
+/// something conflicting was first declared.
+#[derive(Clone)]
+pub struct Label {
+    pub site : Site,
+    pub message : String,
+}
+
+/// Machine-applicable repair of an issue, replacing the
+/// source selected by the site (which may be empty).
+#[derive(Clone)]
+pub struct Edit {
+    pub site : Site,
+    pub replacement : String,
+}
+
+impl Edit {
+    /// Start and end byte of the source replaced.
+    pub fn range(&self) -> Option<(usize, usize)> {
+        let location = &self.site.location;
+        Some((location.byte_offset?, location.eos()?))
+    }
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.site.location.span {
+            Some(0) => write!(f, "insert `{}'", self.replacement),
+            _ if self.replacement.is_empty() => write!(f, "remove this"),
+            _ => write!(f, "replace with `{}'", self.replacement)
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Issue {
     pub kind : Kind,
+    pub code : Code,
     pub site : Site,
     pub message : String,
     note_message : Option<String>,
+    pub labels : Vec<Label>,
+    pub edits : Vec<Edit>,
     pub is_fatal : bool,
 }
 
+impl Kind {
+    /// The error corresponding to a kind of warning.
+    pub fn as_error(self) -> Self {
+        match self {
+            Kind::LexWarn   => Kind::LexError,
+            Kind::ParseWarn => Kind::ParseError,
+            Kind::TypeWarn  => Kind::TypeError,
+            Kind::CompWarn  => Kind::CompError,
+            error => error
+        }
+    }
+}
+
 impl fmt::Display for Kind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let printable = match *self {
@@ -46,12 +96,14 @@ impl fmt::Display for Kind {
 }
 
 impl Issue {
-    #[must_use = "Issue must be displayed"]
-    pub fn new(kind : Kind, site : Site, fmt_msg : String) -> Self {
+    #[must_use = "Issue must be reported"]
+    pub fn new(kind : Kind, code : Code, site : Site, fmt_msg : String) -> Self {
         Self {
-            kind,
+            kind, code,
             site: site.clone(),
             note_message: None,
+            labels: vec![],
+            edits: vec![],
             message: unindent(&fmt_msg)
                 .split('\n')
                 .collect::<Vec<&str>>()
@@ -60,49 +112,490 @@ impl Issue {
         }
     }
 
-    #[must_use = "Issue must be displayed"]
+    #[must_use = "Issue must be reported"]
     pub fn fatal(mut self) -> Self {
         self.is_fatal = true;
         self
     }
 
-    #[must_use = "Issue must be displayed"]
+    #[must_use = "Issue must be reported"]
     pub fn note(mut self, msg : &str) -> Self {
         self.note_message = Some(msg.to_owned());
         self
     }
 
-    pub fn panic(&self) -> ! {
-        panic!("Cannot continue after such an issue: `{}'.",
-            self.message)
+    /// Point out another site relevant to the issue.
+    #[must_use = "Issue must be reported"]
+    pub fn label(mut self, site : Site, msg : &str) -> Self {
+        self.labels.push(Label {
+            site,
+            message: msg.to_owned()
+        });
+        self
+    }
+
+    /// Suggest replacing the source at the site, for `valhallac fix'.
+    #[must_use = "Issue must be reported"]
+    pub fn fix(mut self, site : Site, replacement : &str) -> Self {
+        self.edits.push(Edit {
+            site,
+            replacement: replacement.to_owned()
+        });
+        self
+    }
+
+    /// Whether the issue is an error, as opposed to a warning.
+    pub fn is_error(&self) -> bool {
+        matches!(self.kind,
+              Kind::LexError | Kind::ParseError
+            | Kind::TypeError | Kind::CompError)
     }
 
-    pub fn print(self) -> Self {
-        unsafe {
-            #[cfg(feature="loud-panic")]
-            eprintln!("Issue was: `{}'.", self.message);
-            crate::PANIC_MESSAGE =
-                "Compilation could not continue.";
+    /// Single line JSON object describing the issue, without
+    /// any terminal colouring, for use by other tools.
+    pub fn to_json(&self) -> String {
+        fn string(s : &str) -> String {
+            let mut escaped = String::from("\"");
+            for c in s.chars() {
+                match c {
+                    '"'  => escaped.push_str("\\\""),
+                    '\\' => escaped.push_str("\\\\"),
+                    '\n' => escaped.push_str("\\n"),
+                    '\r' => escaped.push_str("\\r"),
+                    '\t' => escaped.push_str("\\t"),
+                    c if (c as u32) < 0x20 =>
+                        escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                    c => escaped.push(c)
+                }
+            }
+            escaped.push('"');
+            escaped
+        }
+        fn number(n : Option<usize>) -> String {
+            n.map_or(String::from("null"), |n| n.to_string())
         }
 
-        eprintln!("\n{}", self);
-        if self.is_fatal {
-            self.panic();
+        // Path and location of a site, as JSON members.
+        fn site(site : &Site) -> String {
+            let location = &site.location;
+            format!("\"path\":{},\"location\":{{\"line\":{},\"column\":{},\
+                     \"last_column\":{},\"lines\":{},\"columns\":{},\
+                     \"byte_offset\":{},\"span\":{}}}",
+                site.path.as_ref().map_or(String::from("null"),
+                    |p| string(&p.to_string_lossy())),
+                number(location.line),
+                number(location.column),
+                number(location.last_column),
+                number(location.lines),
+                number(location.columns),
+                number(location.byte_offset),
+                number(location.span))
         }
 
-        self
+        let labels = self.labels.iter()
+            .map(|label| format!("{{\"message\":{},{}}}",
+                string(&label.message), site(&label.site)))
+            .collect::<Vec<String>>()
+            .join(",");
+        let edits = self.edits.iter()
+            .map(|edit| format!("{{\"replacement\":{},{}}}",
+                string(&edit.replacement), site(&edit.site)))
+            .collect::<Vec<String>>()
+            .join(",");
+        format!("{{\"kind\":{},\"code\":{},\"message\":{},\"note\":{},\"fatal\":{},{},\
+                 \"labels\":[{}],\"edits\":[{}]}}",
+            string(&format!("{:?}", self.kind)),
+            string(&self.code.to_string()),
+            string(&self.message),
+            self.note_message.as_ref().map_or(String::from("null"), |n| string(n)),
+            self.is_fatal,
+            site(&self.site),
+            labels, edits)
+    }
+
+    /// Hand the issue over to the diagnostics of the current compilation.
+    pub fn report(self, diagnostics : &mut Diagnostics) {
+        #[cfg(feature="loud-panic")]
+        eprintln!("Issue was: `{}'.", self.message);
+        diagnostics.push(self);
+    }
+}
+
+/// Result of a compilation step which may have to be abandoned
+/// because of an issue.  The caller reports the issue and recovers.
+pub type Fallible<T> = Result<T, Issue>;
+
+/// Every issue produced during a compilation, in the order they were found.
+#[derive(Clone, Default)]
+pub struct Diagnostics {
+    issues : Vec<Issue>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, issue : Issue) {
+        self.issues.push(issue);
+    }
+
+    /// Move all issues from another set of diagnostics into this one.
+    pub fn append(&mut self, other : &mut Diagnostics) {
+        self.issues.append(&mut other.issues);
+    }
+
+    pub fn len(&self) -> usize {
+        self.issues.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Whether any of the issues should stop compilation.
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|issue| issue.is_error() || issue.is_fatal)
+    }
+
+    /// Keep only the issues satisfying the predicate.
+    pub fn retain(&mut self, predicate : impl FnMut(&Issue) -> bool) {
+        self.issues.retain(predicate);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Issue> {
+        self.issues.iter()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.issues.iter().filter(|issue| issue.is_error()).count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.len() - self.error_count()
+    }
+
+    /// Order issues by file, then by where they occur in the file.
+    /// Issues without a location are kept last, in the order found.
+    pub fn sort(&mut self) {
+        self.issues.sort_by_key(|issue| {
+            let location = issue.site.location;
+            (issue.site.path.clone(),
+             location.line.unwrap_or(usize::MAX),
+             location.column.unwrap_or(usize::MAX))
+        });
+    }
+
+    /// Summary of issue counts, e.g. "3 errors, 2 warnings".
+    pub fn summary(&self) -> String {
+        fn plural(n : usize, noun : &str) -> String {
+            format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" })
+        }
+        format!("{}, {}",
+            plural(self.error_count(), "error"),
+            plural(self.warning_count(), "warning"))
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Issue;
+    type IntoIter = std::vec::IntoIter<Issue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.issues.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Issue;
+    type IntoIter = std::slice::Iter<'a, Issue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.issues.iter()
+    }
+}
+
+impl From<Issue> for Diagnostics {
+    fn from(issue : Issue) -> Self {
+        Self { issues: vec![issue] }
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &SourceMap::new())
+    }
+}
+
+impl Diagnostics {
+    /// Display every issue, with excerpts from the given sources.
+    pub fn with_sources<'a>(&'a self, sources : &'a SourceMap) -> WithSources<'a, Self> {
+        WithSources { item: self, sources }
     }
 
-    pub fn crash_and_burn(self) -> ! {
-        self.print()
-            .panic()
+    fn render(&self, f : &mut fmt::Formatter, sources : &SourceMap) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "\n{}", issue.with_sources(sources))?;
+        }
+        if self.issues.iter().any(|issue| issue.is_fatal) {
+            writeln!(f, " {} {} {}",
+                "::".white().bold(),
+                "Halt".blue().bold(),
+                "Compilation could not continue.".white())?;
+        }
+        if !self.is_empty() {
+            writeln!(f, "\n {} {}",
+                "::".white().bold(),
+                self.summary().bold())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.issues.iter().map(|issue| &issue.message))
+            .finish()
     }
 }
 
+impl std::error::Error for Diagnostics {}
+
+/// Describe where a site is, e.g. `path:line:column'.
+fn site_position(site : &Site) -> Option<String> {
+    let mut position = if let Some(path) = &site.path {
+        Some(path.to_string_lossy().into_owned())
+    } else if site.repl.is_some() {
+        Some(String::from("<REPL>"))
+    } else { None };
+
+    if let Some(line) = site.location.line {
+        position = Some(match position {
+            Some(file) => format!("{}:{}", file, line),
+            None => line.to_string()
+        });
+    }
+
+    if let Some(column) = site.location.column {
+        position = Some(match position {
+            Some(file) => format!("{}:{}", file, column),
+            None => format!("column: {}", column)
+        });
+    }
+    position
+}
+
+/// Width of the space before the gutter of a source excerpt.
+const INDENT : usize = 5;
+
+/// A selection of source to be underlined in an excerpt.
+struct Mark<'a> {
+    /// First and last line of the selection.
+    first : usize,
+    last  : usize,
+    /// First column on the first line.
+    column : Option<usize>,
+    /// Column just past the selection on the last line.
+    end : Option<usize>,
+    /// Width of a selection on a single line.
+    columns : usize,
+    primary : bool,
+    message : Option<&'a str>,
+}
+
+impl<'a> Mark<'a> {
+    fn new(site : &Site, primary : bool, message : Option<&'a str>) -> Option<Self> {
+        let location = site.location;
+        Some(Self {
+            first: location.line?,
+            last: location.last_line()?,
+            column: location.column,
+            end: location.last_column,
+            columns: location.columns.unwrap_or(1),
+            primary, message
+        })
+    }
+
+    fn is_multi_line(&self) -> bool {
+        self.last > self.first
+    }
+
+    /// Whether the selection continues past the given line.
+    fn continues_after(&self, line : usize) -> bool {
+        self.is_multi_line() && self.first <= line && line < self.last
+    }
+
+    /// Columns of the given line which are selected, as a
+    /// range of the first column, to the column just past it.
+    fn columns_on(&self, line : usize) -> Option<(usize, usize)> {
+        if line < self.first || line > self.last {
+            return None;
+        }
+        let from = if line == self.first { self.column.unwrap_or(1) } else { 1 };
+        let to = if !self.is_multi_line() {
+            from + self.columns
+        } else if line == self.last {
+            self.end.unwrap_or(usize::MAX)
+        } else { usize::MAX };
+        Some((from, to))
+    }
+
+    fn paint(&self, s : &str) -> ColoredString {
+        if self.primary { s.yellow().bold() } else { s.blue().bold() }
+    }
+}
+
+/// Embolden the columns `from' up to `to' of a line.
+fn highlight(line : &str, from : usize, to : usize) -> String {
+    let chars : Vec<char> = line.chars().collect();
+    let i = from.saturating_sub(1).min(chars.len());
+    let j = to.saturating_sub(1).clamp(i, chars.len());
+    let part = |a : usize, b : usize| chars[a..b].iter().collect::<String>();
+    format!("{}{}{}", part(0, i), part(i, j).white().bold(), part(j, chars.len()))
+}
+
+/// Draw the source lines covered by the marks, underlining single
+/// line selections and bracketing multiple line selections in the gutter.
+fn excerpt(f : &mut fmt::Formatter, source : &SourceFile, marks : &[Mark]) -> fmt::Result {
+    // Long selections only show their first and last two lines.
+    let mut shown : Vec<usize> = vec![];
+    for mark in marks {
+        if mark.last - mark.first < 4 {
+            shown.extend(mark.first..=mark.last);
+        } else {
+            shown.extend(&[mark.first, mark.first + 1,
+                           mark.last - 1, mark.last]);
+        }
+    }
+    shown.sort_unstable();
+    shown.dedup();
+
+    let space = " ".repeat(INDENT);
+    let multi : Vec<&Mark> = marks.iter()
+        .filter(|mark| mark.is_multi_line())
+        .collect();
+    let gutter_width = 2 * multi.len();
+    // Gutter between lines, for selections carrying on past `line'.
+    let gutter = |line : usize, upto : usize| multi[..upto].iter()
+        .map(|mark| if mark.continues_after(line) {
+            format!("{} ", mark.paint("|"))
+        } else { String::from("  ") })
+        .collect::<String>();
+    let note = |column : usize, message : &str| format!("{} {}",
+        "|\n+-".yellow(), message.bold())
+        .split('\n')
+        .map(|l| format!("{} {}", " ".repeat(INDENT + gutter_width + column), l))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    writeln!(f, ":\n{}|", space)?;
+    let mut previous : Option<usize> = None;
+    for &line in &shown {
+        if previous.is_some_and(|previous| line > previous + 1) {
+            writeln!(f, "{}", " ...".bold())?;
+        }
+        previous = Some(line);
+
+        // Add space at end to represent line-feed character.
+        let mut content = match source.line(line) {
+            Some(line_str) => format!("{} ", line_str),
+            None => "[**] THIS LINE DOES NOT EXIST! \
+                     Either the file was deleted, \
+                     or this is a bug!".to_string()
+        };
+        if let Some((from, to)) = marks.iter()
+            .find(|mark| mark.primary)
+            .and_then(|mark| mark.columns_on(line)) {
+            content = highlight(&content, from, to);
+        }
+
+        let bracket = multi.iter()
+            .map(|mark| if line == mark.first {
+                format!("{} ", mark.paint("/"))
+            } else if mark.first < line && line <= mark.last {
+                format!("{} ", mark.paint("|"))
+            } else { String::from("  ") })
+            .collect::<String>();
+        writeln!(f, "{}| {}{}",
+            format!("{: >4} ", line).bold(), bracket, content)?;
+
+        // Underline selections on just this line.
+        for mark in marks.iter()
+            .filter(|mark| !mark.is_multi_line() && mark.first == line) {
+            let column = match mark.column {
+                Some(column) => column,
+                None => continue
+            };
+            let underline = if mark.primary { "^" } else { "-" };
+            write!(f, "{}| {}{}{}", space, gutter(line, multi.len()),
+                " ".repeat(gutter_width - 2 * multi.len() + column - 1),
+                mark.paint(&underline.repeat(mark.columns)))?;
+            match mark.message {
+                Some(message) if mark.primary => {
+                    writeln!(f)?;
+                    writeln!(f, "{}", note(column, message))?;
+                },
+                Some(message) => writeln!(f, " {}", mark.paint(message))?,
+                None => writeln!(f)?
+            }
+        }
+
+        // Close off selections ending on this line.
+        for (k, mark) in multi.iter().enumerate()
+            .filter(|(_, mark)| mark.last == line) {
+            let column = mark.end.unwrap_or(2).saturating_sub(1).max(1);
+            let underline = if mark.primary { "^" } else { "-" };
+            let rule = "_".repeat(gutter_width + column - 2 - 2 * k);
+            write!(f, "{}| {}{}", space, gutter(line, k),
+                mark.paint(&format!("|{}{}", rule, underline)))?;
+            match mark.message {
+                Some(message) if mark.primary => {
+                    writeln!(f)?;
+                    writeln!(f, "{}", note(column, message))?;
+                },
+                Some(message) => writeln!(f, " {}", mark.paint(message))?,
+                None => writeln!(f)?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// An issue or set of issues, shown with excerpts of the code they refer to.
+pub struct WithSources<'a, T> {
+    item : &'a T,
+    sources : &'a SourceMap,
+}
+
+impl<'a> fmt::Display for WithSources<'a, Issue> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.item.render(f, self.sources)
+    }
+}
+
+impl<'a> fmt::Display for WithSources<'a, Diagnostics> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.item.render(f, self.sources)
+    }
+}
+
+/// Without any sources, issues are shown without excerpts.
 impl fmt::Display for Issue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, &SourceMap::new())
+    }
+}
+
+impl Issue {
+    /// Display the issue, with excerpts from the given sources.
+    pub fn with_sources<'a>(&'a self, sources : &'a SourceMap) -> WithSources<'a, Self> {
+        WithSources { item: self, sources }
+    }
+
+    fn render(&self, f : &mut fmt::Formatter, sources : &SourceMap) -> fmt::Result {
         writeln!(f, "{}{} {}",
-            "issue".bold().red(),
+            format!("issue[{}]", self.code).bold().red(),
             ":".white(),
             self.message.bold())?;
         write!(f, "{}", "".clear())?;
@@ -110,117 +603,61 @@ impl fmt::Display for Issue {
         write!(f, " ==> {kind}",
             kind = self.kind.to_string().bold())?;
 
-        let mut got_file = false;
-
-        if let Some(path) = &self.site.path {
-            got_file = true;
-            write!(f, " in ({}", path.to_string_lossy())?;
-        } else if self.site.repl {
-            got_file = true;
-            write!(f, " in (<REPL>")?;
+        if let Some(position) = site_position(&self.site) {
+            write!(f, " in ({})", position)?;
         }
 
-        if let Some(line) = self.site.location.line {
-            if got_file {
-                write!(f, ":{}", line)?;
-            } else {
-                got_file = true;
-                write!(f, " in ({}", line)?;
-            }
-        }
+        // Labels in the same file are shown in the same excerpt.
+        let (local, elsewhere) : (Vec<&Label>, Vec<&Label>) = self.labels.iter()
+            .partition(|label| SourceId::of(&self.site).is_some()
+                            && SourceId::of(&label.site) == SourceId::of(&self.site)
+                            && label.site.location.line.is_some());
 
-        if let Some(column) = self.site.location.column {
-            if got_file {
-                write!(f, ":{}", column)?;
-            } else {
-                got_file = true;
-                write!(f, " at (column: {}", column)?;
+        let mut excerpted = false;
+        let mut noted = false;
+        if self.site.path.is_some() || self.site.repl.is_some() {
+            if let Some(source) = sources.for_site(&self.site) {
+                let mut marks : Vec<Mark> = vec![];
+                if let Some(mark) = Mark::new(&self.site, true,
+                                              self.note_message.as_deref()) {
+                    marks.push(mark);
+                }
+                marks.extend(local.iter().filter_map(|label|
+                    Mark::new(&label.site, false, Some(&label.message))));
+
+                if !marks.is_empty() {
+                    excerpt(f, source, &marks)?;
+                    excerpted = true;
+                    noted = marks[0].primary
+                        && (marks[0].is_multi_line() || marks[0].column.is_some());
+                }
             }
         }
+        if !excerpted {
+            writeln!(f)?;
+        }
 
-        if got_file {
-            write!(f, ")")?;
+        for label in elsewhere {
+            write!(f, "{}{} {}", " ".repeat(INDENT), "-".blue().bold(),
+                label.message.bold())?;
+            if let Some(position) = site_position(&label.site) {
+                write!(f, " in ({})", position)?;
+            }
+            writeln!(f)?;
         }
 
-        let indent = 5;
-
-        let mut opened_file = false;
-        let mut multi_line = false;
-        if let Some(path) = &self.site.path {
-            let file_result = fs::File::open(path);
-            if let Ok(file) = file_result {
-                opened_file = true;
-                if let Location {
-                    line: Some(line),
-                    lines: Some(lines),
-                    ..
-                } = self.site.location {
-                    if lines == 1 {
-                        let mut line_content = if let Some(Ok(line_str)) =
-                            BufReader::new(file)
-                                .lines().nth(line - 1) {
-                            // Add space at end to represent
-                            // line-feed character.
-                            line_str + " "
-                        } else {
-                            "[**] THIS LINE DOES NOT EXIST! \
-                                  Either the file was deleted, \
-                                  or this is a bug!".to_string()
-                        };
-                        if let Location {
-                            column: Some(column),
-                            last_column: Some(last_column),
-                            ..
-                        } = self.site.location {
-                            let (i, j) = (column - 1, last_column - 1);
-                            line_content = format!("{}{}{}",
-                                &line_content[..i],
-                                &line_content[i..j]
-                                    .white().bold(),
-                                &line_content[j..]);
-                        }
-                        writeln!(f, ":\n{space}|\n{line_num}| {line_str}",
-                            space = " ".repeat(indent),
-                            line_num = format!("{: >4} ", line).bold(),
-                            line_str = line_content)?;
-                    } else {  // Error spans multiple lines
-                        multi_line = true;
-                        // TODO: Display the lines.
-                    }
-                }
+        if !noted {
+            if let Some(note) = &self.note_message {
+                writeln!(f, "{} {}", "|\n+-".yellow(), note.bold())?;
             }
         }
 
-        let note_ascii = self.note_message.as_ref().map(
-            |some| format!("{} {}",
-                "|\n+-".yellow(), some.bold()));
-
-        if let Some(column) = self.site.location.column {
-            if opened_file {
-                if multi_line {
-                    // TODO: Show the arrows for the longest line in the
-                    //       multiple lines.
-                } else {
-                    let columns = self.site.location.columns
-                        .unwrap_or(1);
-                     writeln!(f, "{space}|{: >offset$}",
-                        "^".repeat(columns).yellow().bold(),
-                        space=" ".repeat(indent),
-                        offset=(column + columns))?;
-                    if let Some(note_fmt) = note_ascii {
-                        let indented = note_fmt
-                            .split("\n")
-                            .map(|l| format!("{space} {line}",
-                                line=l,
-                                space=" ".repeat(indent + column)))
-                            .collect::<Vec<String>>()
-                            .join("\n");
-                        writeln!(f, "{}", indented)?;
-                    }
-                }
+        for edit in &self.edits {
+            write!(f, "{}{} {}", " ".repeat(INDENT), "fix:".green().bold(), edit)?;
+            if let Some(position) = site_position(&edit.site) {
+                write!(f, " at ({})", position)?;
             }
-        } else if let Some(note_fmt) = note_ascii {
-            writeln!(f, "{}", note_fmt)?;
+            writeln!(f)?;
         }
 
         Ok(())
@@ -229,28 +666,124 @@ impl fmt::Display for Issue {
 
 #[macro_export]
 macro_rules! issue {
-    ($type:ident, $site:expr, $message:expr) => {
-        #[must_use = "Issue must be displayed"] {
-            issue::Issue::new(issue::Kind::$type, $site.clone(),
-                String::from($message))
-        }
+    ($type:ident, $code:ident, $site:expr, $message:expr) => {
+        issue::Issue::new(issue::Kind::$type, $crate::codes::$code,
+            $site.clone(), String::from($message))
     };
 
-    ($type:ident, $site:expr, $message:expr, $($form:expr),*) => {
-        #[must_use = "Issue must be displayed"] {
-            issue::Issue::new(issue::Kind::$type, $site.clone(),
-                format!($message, $($form),*))
-        }
+    ($type:ident, $code:ident, $site:expr, $message:expr, $($form:expr),*) => {
+        issue::Issue::new(issue::Kind::$type, $crate::codes::$code,
+            $site.clone(), format!($message, $($form),*))
     };
 }
 
 #[macro_export]
 macro_rules! fatal {
     ($type:ident, $($args:tt)*) => {
-        #[must_use = "Issue must be displayed"] {
-            let mut value = issue!($type, $($args)*);
-            value.is_fatal = true;
-            value
-        }
+        issue!($type, $($args)*).fatal()
     };
 }
+
+#[cfg(test)]
+mod rendering {
+    use super::*;
+    use crate::issue;
+    use crate::source::SourceId;
+
+    /// Text without any terminal colouring.
+    fn plain(text : &str) -> String {
+        let mut plain = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else { plain.push(c); }
+        }
+        plain
+    }
+
+    fn sources() -> SourceMap {
+        let mut sources = SourceMap::new();
+        sources.add(SourceId::from("test.vh"),
+            String::from("a : Nat\nb = f (1,\n  2)\na = 3\n"));
+        sources
+    }
+
+    #[test]
+    fn json_is_not_coloured() {
+        colored::control::set_override(true);
+        let issue = issue!(TypeError, V0023, Site::single_line(2, 1, 1, 1, 8)
+                .with_filename("test.vh"), "Cannot mutate `a'.")
+            .note("Say \"b\"\ninstead.")
+            .label(Site::single_line(1, 1, 1, 1, 0).with_filename("test.vh"),
+                   "first bound here");
+        let json = issue.to_json();
+        assert!(!json.contains('\x1b'));
+        assert_eq!(json, concat!(
+            r#"{"kind":"TypeError","code":"V0023","message":"Cannot mutate `a'.","#,
+            r#""note":"Say \"b\"\ninstead.","fatal":false,"path":"test.vh","#,
+            r#""location":{"line":2,"column":1,"last_column":2,"lines":1,"columns":1,"#,
+            r#""byte_offset":8,"span":1},"labels":[{"message":"first bound here","#,
+            r#""path":"test.vh","location":{"line":1,"column":1,"last_column":2,"#,
+            r#""lines":1,"columns":1,"byte_offset":0,"span":1}}],"edits":[]}"#));
+    }
+
+    #[test]
+    fn multi_line_selections() {
+        let mut site = Site::single_line(2, 5, 0, 12, 12).with_filename("test.vh");
+        site.location.lines = Some(2);
+        site.location.last_column = Some(5);
+        let issue = issue!(TypeError, V0011, site, "Cannot apply `f'.")
+            .note("`f' is not a function.");
+        assert_eq!(plain(&issue.with_sources(&sources()).to_string()), concat!(
+            "issue[V0011]: Cannot apply `f'.\n",
+            " ==> Typing Error in (test.vh:2:5):\n",
+            "     |\n",
+            "   2 | / b = f (1, \n",
+            "   3 | |   2) \n",
+            "     | |____^\n",
+            "            |\n",
+            "            +- `f' is not a function.\n"));
+    }
+
+    #[test]
+    fn labels_share_the_excerpt() {
+        let issue = issue!(CompError, V0023, Site::single_line(4, 1, 1, 1, 22)
+                .with_filename("test.vh"), "Cannot mutate `a'.")
+            .label(Site::single_line(1, 1, 1, 1, 0).with_filename("test.vh"),
+                   "first bound here")
+            .label(Site::single_line(3, 7, 1, 1, 40).with_filename("other.vh"),
+                   "also bound here");
+        // Labels in other files are listed after the excerpt.
+        assert_eq!(plain(&issue.with_sources(&sources()).to_string()), concat!(
+            "issue[V0023]: Cannot mutate `a'.\n",
+            " ==> Compilation Error in (test.vh:4:1):\n",
+            "     |\n",
+            "   1 | a : Nat \n",
+            "     | - first bound here\n",
+            " ...\n",
+            "   4 | a = 3 \n",
+            "     | ^\n",
+            "     - also bound here in (other.vh:3:7)\n"));
+    }
+
+    #[test]
+    fn excerpts_from_memory() {
+        let mut sources = SourceMap::new();
+        sources.add(SourceId::from("not/on/disk.vh"), String::from("a = b\n"));
+        sources.add(SourceId::Repl(3), String::from("c = d\n"));
+        let file = issue!(ParseError, V0010, Site::single_line(1, 5, 1, 1, 4)
+            .with_filename("not/on/disk.vh"), "Variable `b' is not declared.");
+        let repl = issue!(ParseError, V0010, Site::single_line(1, 5, 1, 1, 4)
+            .with_repl(3), "Variable `d' is not declared.");
+
+        assert!(plain(&file.with_sources(&sources).to_string())
+            .ends_with("   1 | a = b \n     |     ^\n"));
+        assert!(plain(&repl.with_sources(&sources).to_string())
+            .ends_with("(<REPL>:1:5):\n     |\n   1 | c = d \n     |     ^\n"));
+        // Without the sources, there is nothing to excerpt.
+        assert_eq!(plain(&file.to_string()), concat!(
+            "issue[V0010]: Variable `b' is not declared.\n",
+            " ==> Grammar Error in (not/on/disk.vh:1:5)\n"));
+    }
+}
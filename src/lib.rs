@@ -5,8 +5,8 @@
 #![allow(clippy::needless_return)]
 #![allow(clippy::single_match)]
 #![allow(clippy::new_ret_no_self)]
-
-#![feature(stmt_expr_attributes)]
+// Issues are only ever returned on the unhappy path.
+#![allow(clippy::result_large_err)]
 
 include!(concat!(env!("OUT_DIR"), "/version.rs"));
 
@@ -15,10 +15,26 @@ pub const VERSION : (u8, u8, u8) = read_version();
 /// Source code sites (location, line, filename, etc.).
 pub mod site;
 
+/// Registry of source code being compiled, used to show
+/// excerpts of code in issues.
+pub mod source;
+
+/// Stable codes identifying each issue, with explanations.
+pub mod codes;
+
 /// Issue messages (warnings, errors, info, etc.).
 #[macro_use]
 pub mod issue;
 
+/// Levels at which warnings are allowed, reported or denied.
+pub mod lints;
+
+/// Applying the repairs suggested by issues to source code.
+pub mod fix;
+
+/// Suggestions for misspelt names.
+pub mod suggest;
+
 /// Syntax submodule, responsible for lexical analysis,
 /// parsing and static analysis.
 pub mod syntax;
@@ -28,53 +44,51 @@ pub mod syntax;
 pub mod compiler;
 
 pub use syntax::parse_source;
-
-/// Parses the contents of a file with path `filename : &str`.
-pub fn parse(filename : &str) -> syntax::ast::Root {
-    syntax::parse_file(filename)
+pub use issue::Diagnostics;
+pub use source::{SourceMap, SourceId};
+
+/// Parses the contents of a file with path `filename : &str`,
+/// keeping its source in `sources` to render issues from.
+pub fn parse(filename : &str, sources : &mut SourceMap)
+    -> Result<syntax::ast::Root, Diagnostics> {
+    syntax::parse_file(filename, sources)
 }
 
 /// Compile the parse tree.
-pub fn compile(root : &syntax::ast::Root) -> compiler::block::LocalBlock {
+/// Warnings produced whilst compiling are kept in the block's `issues`.
+pub fn compile(root : &syntax::ast::Root)
+    -> Result<compiler::block::LocalBlock<'_>, Diagnostics> {
     let mut code_block = compiler::block::LocalBlock::new("<main>", &root.filename);
 
     code_block.generate(&root.branches);
+    root.lints.apply(&mut code_block.issues);
+    code_block.issues.sort();
+    if code_block.issues.has_errors() {
+        return Err(code_block.issues);
+    }
 
     #[cfg(feature="debug")]
     println!("Code Blocks:\n{}", code_block);
-    code_block
+    Ok(code_block)
 }
 
-pub fn binary_blob(block : &compiler::block::LocalBlock) -> Vec<u8> {
-    compiler::marshal::generate_binary(block)
+/// Marshal a compiled code block into the bytecode format read by the VM.
+pub fn binary_blob(block : &compiler::block::LocalBlock) -> Result<Vec<u8>, Diagnostics> {
+    Ok(compiler::marshal::generate_binary(block))
 }
 
-// Set panic message for compiler bugs and issue messages.
+// Set panic message for compiler bugs.
 use std::panic;
 use colored::*;
 
-pub static mut PANIC_MESSAGE : &str = "";
-
+/// Issues never panic, so any panic is a compiler bug, this
+/// hook reports it as such.  Only meant to be set by executables.
 pub fn set_panic() {
-    unsafe {
-        panic::set_hook(Box::new(|msg| {
-            if PANIC_MESSAGE.is_empty() {
-                eprintln!("\n{}", "The compiler panicked! This is a bug."
-                    .white().bold());
-                eprintln!("{} {}\n",
-                    ">>>".blue(),
-                    msg.to_string().white());
-            } else {
-                eprintln!(" {} {} {}",
-                    "::".white().bold(),
-                    "Halt".blue().bold(),
-                    PANIC_MESSAGE.white());
-                #[cfg(any(feature="loud-panic", feature="debug"))] {
-                    eprintln!("{} {}\n",
-                        ">>>".blue(),
-                        msg.to_string().white());
-                }
-            }
-        }));
-    }
+    panic::set_hook(Box::new(|msg| {
+        eprintln!("\n{}", "The compiler panicked! This is a bug."
+            .white().bold());
+        eprintln!("{} {}\n",
+            ">>>".blue(),
+            msg.to_string().white());
+    }));
 }
@@ -0,0 +1,160 @@
+use crate::{issue, site::Site};
+use issue::{Issue, Kind, Diagnostics};
+
+use crate::codes::Code;
+use crate::syntax::token::Token;
+
+use std::str::FromStr;
+
+/// How a warning is treated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    /// Warning is silently dropped.
+    Allow,
+    Warn,
+    /// Warning becomes an error.
+    Deny,
+}
+
+/// The warnings which a level is given for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lint {
+    /// Every warning.
+    Warnings,
+    /// Warnings of a kind, e.g. `LexWarn'.
+    Kind(Kind),
+    /// Warnings with a specific code, e.g. `V0002'.
+    Code(Code),
+}
+
+impl Lint {
+    fn applies_to(&self, issue : &Issue) -> bool {
+        match self {
+            Lint::Warnings => true,
+            Lint::Kind(kind) => *kind == issue.kind,
+            Lint::Code(code) => *code == issue.code,
+        }
+    }
+
+    /// Levels given for more specific lints take precedence.
+    fn specificity(&self) -> u8 {
+        match self {
+            Lint::Warnings => 0,
+            Lint::Kind(_)  => 1,
+            Lint::Code(_)  => 2,
+        }
+    }
+}
+
+/// Parses lints written as `warnings', a warning kind
+/// (e.g. `TypeWarn') or a code (e.g. `V0002').
+impl FromStr for Lint {
+    type Err = ();
+
+    fn from_str(s : &str) -> Result<Self, Self::Err> {
+        let lint = match s {
+            "warnings"  => Lint::Warnings,
+            "LexWarn"   => Lint::Kind(Kind::LexWarn),
+            "ParseWarn" => Lint::Kind(Kind::ParseWarn),
+            "TypeWarn"  => Lint::Kind(Kind::TypeWarn),
+            "CompWarn"  => Lint::Kind(Kind::CompWarn),
+            code if code.starts_with(['V', 'v']) => {
+                let code : Code = code.parse().map_err(|_| ())?;
+                code.explanation().ok_or(())?;
+                Lint::Code(code)
+            },
+            _ => return Err(())
+        };
+        Ok(lint)
+    }
+}
+
+/// A level given for a lint, and where it was given.
+#[derive(Clone)]
+struct Setting {
+    lint : Lint,
+    level : Level,
+    site : Site,
+    /// How the level was given, e.g. `-D warnings'.
+    origin : String,
+}
+
+/// Levels of warnings, as given on the command line or by
+/// file-level `!allow' and `!deny' pragmas.  Errors are unaffected.
+#[derive(Clone, Default)]
+pub struct LintLevels {
+    settings : Vec<Setting>,
+}
+
+impl LintLevels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give the level of a lint, overriding any given before it.
+    pub fn set(&mut self, lint : Lint, level : Level, site : Site, origin : &str) {
+        self.settings.push(Setting {
+            lint, level, site,
+            origin: origin.to_owned()
+        });
+    }
+
+    /// The most specific setting which applies to the issue, or if
+    /// there are several of the same specificity, the last one given.
+    fn setting_for(&self, issue : &Issue) -> Option<&Setting> {
+        if issue.is_error() {
+            return None;
+        }
+        self.settings.iter()
+            .filter(|setting| setting.lint.applies_to(issue))
+            .max_by_key(|setting| setting.lint.specificity())
+    }
+
+    pub fn level_of(&self, issue : &Issue) -> Level {
+        self.setting_for(issue)
+            .map_or(Level::Warn, |setting| setting.level)
+    }
+
+    /// Drop allowed warnings, and turn denied warnings into errors.
+    pub fn apply(&self, issues : &mut Diagnostics) {
+        let all = std::mem::take(issues);
+        for issue in all {
+            match self.setting_for(&issue) {
+                Some(setting) if setting.level == Level::Allow => continue,
+                Some(setting) if setting.level == Level::Deny => {
+                    let mut denied = issue.label(setting.site.clone(),
+                        &format!("denied by `{}'", setting.origin));
+                    denied.kind = denied.kind.as_error();
+                    issues.push(denied);
+                },
+                _ => issues.push(issue)
+            }
+        }
+    }
+
+    /// Read levels from a pragma token, e.g. `!allow V0002, TypeWarn'.
+    pub fn pragma(&mut self, token : &Token, filename : &str, issues : &mut Diagnostics) {
+        let mut words = token.string
+            .split(|c : char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty());
+
+        let directive = words.next().unwrap_or_default();
+        let level = match directive {
+            "!allow" => Level::Allow,
+            "!deny"  => Level::Deny,
+            _ => return
+        };
+        let site = token.location.with_filename(filename);
+        for name in words {
+            match name.parse() {
+                Ok(lint) => self.set(lint, level, site.clone(),
+                                     &format!("{} {}", directive, name)),
+                Err(_) => issue!(LexWarn, V0030, site,
+                    "Unknown lint `{}' in pragma.", name)
+                        .note("Expected `warnings', a warning kind \
+                               (e.g. `TypeWarn'), or a code (e.g. `V0002').")
+                        .report(issues)
+            }
+        }
+    }
+}
@@ -37,8 +37,8 @@ pub const NULL_LOCATION : Location = Location {
 pub struct Site {
     /// Source may or may not come from a file.
     pub path : Option<PathBuf>,
-    /// Source is from a REPL instance.
-    pub repl : bool,
+    /// Source is from a REPL instance, numbered by its input.
+    pub repl : Option<usize>,
 
     /// The specific piece of source / AST node may come from
     ///  a specific column and line from within the file.
@@ -50,7 +50,7 @@ pub struct Site {
 
 pub const FAKE_SITE : Site = Site {
     path: None,
-    repl: false,
+    repl: None,
     location: NULL_LOCATION,
     fake: true,
 };
@@ -59,24 +59,16 @@ impl Location {
     /// Last line in selection.
     #[inline]
     pub fn last_line(&self) -> Option<usize> {
-        match self.line {
-            Some(line) => if let Some(lines) = self.lines {
-                Some(line + lines - 1)
-            } else { None }
-            None => None
-        }
+        self.line.and_then(|line|
+            self.lines.map(|lines| line + lines - 1))
     }
 
     /// Number of characters from the last column to the beginning of file,
     /// (offset in bytes from the top of file to last column).
     #[inline]
     pub fn eos(&self) -> Option<usize> {
-        match self.byte_offset {
-            Some(bof) => if let Some(span) = self.span {
-                Some(bof + span)
-            } else { None }
-            None => None
-        }
+        self.byte_offset.and_then(|bof|
+            self.span.map(|span| bof + span))
     }
 }
 
@@ -107,6 +99,34 @@ impl Site {
         s.path = Some(PathBuf::from(name));
         s
     }
+
+    /// Empty selection at the beginning of this one.
+    pub fn start(&self) -> Self {
+        let mut s = self.clone();
+        s.location.lines = s.location.line.map(|_| 1);
+        s.location.last_column = s.location.column;
+        s.location.columns = Some(0);
+        s.location.span = Some(0);
+        s
+    }
+
+    /// Empty selection just past the end of this one.
+    pub fn end(&self) -> Self {
+        let mut s = self.clone();
+        s.location.line = self.location.last_line();
+        s.location.lines = s.location.line.map(|_| 1);
+        s.location.column = self.location.last_column;
+        s.location.columns = Some(0);
+        s.location.byte_offset = self.location.eos();
+        s.location.span = Some(0);
+        s
+    }
+
+    pub fn with_repl(&self, input : usize) -> Self {
+        let mut s = self.clone();
+        s.repl = Some(input);
+        s
+    }
 }
 
 impl std::default::Default for Site {
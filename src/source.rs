@@ -0,0 +1,111 @@
+use crate::site::Site;
+
+use std::path::PathBuf;
+use std::collections::HashMap;
+
+/// Identifies where a piece of source code came from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SourceId {
+    File(PathBuf),
+    /// Numbered input of a REPL session.
+    Repl(usize),
+}
+
+impl SourceId {
+    /// The source a site points into, if it is a real one.
+    pub fn of(site : &Site) -> Option<Self> {
+        if let Some(path) = &site.path {
+            Some(SourceId::File(path.clone()))
+        } else { site.repl.map(SourceId::Repl) }
+    }
+}
+
+impl From<&str> for SourceId {
+    fn from(path : &str) -> Self {
+        SourceId::File(PathBuf::from(path))
+    }
+}
+
+/// Source code exactly as it was given to the compiler.
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    pub id : SourceId,
+    pub content : String,
+    /// Byte offset of the beginning of each line.
+    line_starts : Vec<usize>,
+}
+
+impl SourceFile {
+    pub fn new(id : SourceId, content : String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { id, content, line_starts }
+    }
+
+    /// Contents of the numbered line (counting from 1),
+    /// without its line-feed.
+    pub fn line(&self, line : usize) -> Option<&str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self.line_starts.get(line)
+            .map_or(self.content.len(), |next| next - 1);
+        let line_str = &self.content[start..end];
+        Some(line_str.strip_suffix('\r').unwrap_or(line_str))
+    }
+
+    /// Number of lines in the source.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+}
+
+/// Every source seen in a compilation session, so that
+/// issues can show excerpts of the code they refer to.
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    files : HashMap<SourceId, SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register source code, replacing any older version of it.
+    pub fn add(&mut self, id : SourceId, content : String) -> &SourceFile {
+        self.files.insert(id.clone(), SourceFile::new(id.clone(), content));
+        &self.files[&id]
+    }
+
+    pub fn get(&self, id : &SourceId) -> Option<&SourceFile> {
+        self.files.get(id)
+    }
+
+    /// Source that a site points into.
+    pub fn for_site(&self, site : &Site) -> Option<&SourceFile> {
+        self.get(&SourceId::of(site)?)
+    }
+}
+
+#[cfg(test)]
+mod lines {
+    use super::*;
+
+    #[test]
+    fn lines_are_numbered_from_one() {
+        let source = SourceFile::new(SourceId::Repl(1), String::from("a\r\nb\n\nc"));
+        assert_eq!(source.line_count(), 4);
+        let lines : Vec<Option<&str>> = (0..=5).map(|line| source.line(line)).collect();
+        assert_eq!(lines, [None, Some("a"), Some("b"), Some(""), Some("c"), None]);
+    }
+
+    #[test]
+    fn sources_are_replaced() {
+        let mut sources = SourceMap::new();
+        sources.add(SourceId::from("a.vh"), String::from("old"));
+        sources.add(SourceId::from("a.vh"), String::from("new"));
+        let site = Site::single_line(1, 1, 1, 1, 0).with_filename("a.vh");
+        assert_eq!(sources.for_site(&site).and_then(|source| source.line(1)), Some("new"));
+        assert!(sources.for_site(&Site::new()).is_none());
+    }
+}
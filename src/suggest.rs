@@ -0,0 +1,79 @@
+/// Number of single character insertions, deletions, substitutions
+/// and swaps of adjacent characters needed to turn one string into the other.
+pub fn edit_distance(a : &str, b : &str) -> usize {
+    let a : Vec<char> = a.chars().collect();
+    let b : Vec<char> = b.chars().collect();
+    // Distances between prefixes of `a' and `b', two rows back,
+    //  one row back and the current row.
+    let mut before : Vec<usize> = vec![0; b.len() + 1];
+    let mut previous : Vec<usize> = (0..=b.len()).collect();
+    let mut current : Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (previous[j - 1] + cost)
+                .min(previous[j] + 1)
+                .min(current[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Picks the candidate most likely meant instead of `name', if any
+/// is close enough.  A candidate differing only in case is preferred.
+pub fn closest<'a, I>(name : &str, candidates : I) -> Option<&'a str>
+    where I : IntoIterator<Item=&'a str> {
+    let length = name.chars().count();
+    // Allow about one mistake in every three characters,
+    // but never a completely different name.
+    let threshold = (length / 3).max(1).min(length.saturating_sub(1));
+
+    candidates.into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| {
+            let distance = if candidate.to_lowercase() == name.to_lowercase() {
+                0
+            } else { edit_distance(name, candidate) };
+            (distance, candidate)
+        })
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Note for an issue, suggesting the closest candidate.
+pub fn did_you_mean<'a, I>(name : &str, candidates : I) -> Option<String>
+    where I : IntoIterator<Item=&'a str> {
+    closest(name, candidates)
+        .map(|candidate| format!("Did you mean `{}'?", candidate))
+}
+
+#[cfg(test)]
+mod suggestions {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance("count", "count"), 0);
+        assert_eq!(edit_distance("coutn", "count"), 1);
+        assert_eq!(edit_distance("cont",  "count"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "Nat"), 3);
+    }
+
+    #[test]
+    fn closest_candidates() {
+        let names = ["Nat", "Int", "Real", "count"];
+        assert_eq!(closest("nat", names.iter().copied()), Some("Nat"));
+        assert_eq!(closest("cuont", names.iter().copied()), Some("count"));
+        assert_eq!(closest("x", names.iter().copied()), None);
+        assert_eq!(closest("String", names.iter().copied()), None);
+    }
+}
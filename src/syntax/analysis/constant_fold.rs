@@ -3,67 +3,374 @@
  * A static optimisation that relieves the runtime of having to perform
  * pre-computable trivial calculations, by doing them at compile time
  * instead.  This function takes a node and recurses down, looking
- * for arithmetic operations containing exactly two numeric type nodes
- * as operands, and performs the stated operation.
+ * for operations on constant operands (numbers, strings and the
+ * booleans `:true' and `:false'), and performs the stated operation.
+ * Casts of numbers to stronger numeric types (e.g. those inserted by
+ * the type balancer) are folded too, so that `1 / 3' is folded to
+ * an exact rational.  Branches are folded repeatedly, until there
+ * is nothing more to fold.
+ *
+ * Operations that would divide by zero, or give a real that is
+ * infinite or not a number, are left for the runtime, with a warning.
  */
 
 
+use std::cmp::Ordering;
+
 use super::ast;
-use ast::Nodes;
-
-fn const_fold(node : &Nodes) -> Nodes {
-    if let Nodes::Call(call) = node {
-        if call.is_binary() {
-            let bin_op = call.callee.call().unwrap().callee.ident().unwrap();
-            let left  = const_fold(&call.callee.call().unwrap().operands[0]);
-            let right = const_fold(&call.operands[0]);
-
-            let def = Nodes::Call(ast::CallNode {
-                callee: Box::new(Nodes::Call(ast::CallNode {
-                    callee: Box::new(const_fold(&*call.callee.call().unwrap().callee)),
-                    operands: vec![left.clone()],
-                    return_type: call.callee.yield_type(),
-                    site: call.callee.call().unwrap().site.clone()
-                })),
-                operands: vec![right.clone()],
+use ast::{Nodes, Numerics, StaticTypes};
+
+use crate::issue;
+use issue::Diagnostics;
+
+/// The numeric type a cast is to, if it is one.
+fn cast_type(name : &str) -> Option<StaticTypes> {
+    Some(match name {
+        "Nat"  => StaticTypes::TNatural,
+        "Int"  => StaticTypes::TInteger,
+        "Rat"  => StaticTypes::TRational,
+        "Real" => StaticTypes::TReal,
+        _ => return None
+    })
+}
+
+/// Value of a boolean constant, i.e. `:true' or `:false'.
+fn boolean(node : &Nodes) -> Option<bool> {
+    match node.sym()?.value.as_str() {
+        "true"  => Some(true),
+        "false" => Some(false),
+        _ => None
+    }
+}
+
+fn boolean_node(value : bool, call : &ast::CallNode) -> Nodes {
+    let name = if value { ":true" } else { ":false" };
+    ast::SymNode::new(name, call.site.clone())
+}
+
+struct Folder<'a> {
+    filename : &'a str,
+    issues : Diagnostics,
+    /// Whether anything was folded in this pass.
+    folded : bool,
+}
+
+impl<'a> Folder<'a> {
+    /// Check that a folded real is finite, reporting it otherwise.
+    fn is_representable(&mut self, value : &Numerics, call : &ast::CallNode) -> bool {
+        let site = call.site.with_filename(self.filename);
+        match value {
+            Numerics::Real(r) if r.is_nan() => issue!(CompWarn, V0039, site,
+                "Constant expression is not a number.")
+                .note("The expression is left to be evaluated at runtime.")
+                .report(&mut self.issues),
+            Numerics::Real(r) if r.is_infinite() => issue!(CompWarn, V0038, site,
+                "Constant expression overflows, it is too large to be a real.")
+                .note("The expression is left to be evaluated at runtime.")
+                .report(&mut self.issues),
+            _ => return true
+        }
+        false
+    }
+
+    fn division_by_zero(&mut self, call : &ast::CallNode) {
+        issue!(CompWarn, V0037, call.site.with_filename(self.filename),
+            "Division by zero in constant expression.")
+            .note("The division is left to be performed at runtime.")
+            .report(&mut self.issues);
+    }
+
+    /// Operate on two numbers, `None' if it cannot be done at compile time.
+    fn arithmetic(&mut self, op : &str, left : &Numerics, right : &Numerics,
+                  call : &ast::CallNode) -> Option<Numerics> {
+        let (l_value, r_value) = (left.clone(), right.clone());
+        let value = match op {
+            "+" => l_value + r_value,
+            "-" => l_value - r_value,
+            "*" => l_value * r_value,
+            "/" | "div" | "mod" if r_value.is_zero() => {
+                self.division_by_zero(call);
+                return None;
+            },
+            "/" => l_value / r_value,
+            "div" => l_value.truncated_div(&r_value),
+            "mod" => l_value.modulo(&r_value),
+            "^" => {
+                if l_value.is_zero() && r_value.compare(&Numerics::Natural(0)) == Some(Ordering::Less) {
+                    self.division_by_zero(call);
+                    return None;
+                }
+                l_value.pow(&r_value)?
+            },
+            _ => return None
+        };
+        if !self.is_representable(&value, call) {
+            return None;
+        }
+        Some(value)
+    }
+
+    /// Fold an operation on two constants, if possible.
+    fn binary(&mut self, op : &str, left : &Nodes, right : &Nodes,
+              call : &ast::CallNode) -> Option<Nodes> {
+        if let (Some(l), Some(r)) = (left.num(), right.num()) {
+            let ordering = l.value.compare(&r.value);
+            let comparison = match op {
+                "==" => Some(ordering == Some(Ordering::Equal)),
+                "/=" => Some(ordering != Some(Ordering::Equal)),
+                "<"  => Some(ordering? == Ordering::Less),
+                "<=" => Some(ordering? != Ordering::Greater),
+                ">"  => Some(ordering? == Ordering::Greater),
+                ">=" => Some(ordering? != Ordering::Less),
+                _ => None
+            };
+            if let Some(result) = comparison {
+                return Some(boolean_node(result, call));
+            }
+            let value = self.arithmetic(op, &l.value, &r.value, call)?;
+            return Some(Nodes::Num(ast::NumNode { value, site: call.site.clone() }));
+        }
+
+        if let Some(l) = left.num() {
+            if op == "cast" {
+                let value = l.value.cast(&cast_type(right.get_name()?)?)?;
+                if !self.is_representable(&value, call) {
+                    return None;
+                }
+                return Some(Nodes::Num(ast::NumNode { value, site: call.site.clone() }));
+            }
+        }
+
+        if let (Some(l), Some(r)) = (left.str(), right.str()) {
+            return match op {
+                "++" => Some(ast::StrNode::new(&(l.value.clone() + &r.value), call.site.clone())),
+                "==" => Some(boolean_node(l.value == r.value, call)),
+                "/=" => Some(boolean_node(l.value != r.value, call)),
+                _ => None
+            };
+        }
+
+        if let (Some(l), Some(r)) = (boolean(left), boolean(right)) {
+            match op {
+                "and" => return Some(boolean_node(l && r, call)),
+                "or"  => return Some(boolean_node(l || r, call)),
+                _ => ()
+            }
+        }
+
+        if let (Some(l), Some(r)) = (left.sym(), right.sym()) {
+            return match op {
+                "==" => Some(boolean_node(l.value == r.value, call)),
+                "/=" => Some(boolean_node(l.value != r.value, call)),
+                _ => None
+            };
+        }
+        None
+    }
+
+    /// Fold an operation on one constant, if possible.
+    fn unary(&mut self, op : &str, operand : &Nodes,
+             call : &ast::CallNode) -> Option<Nodes> {
+        match op {
+            "-" => {
+                let value = -operand.num()?.value.clone();
+                Some(Nodes::Num(ast::NumNode { value, site: call.site.clone() }))
+            },
+            "not" => Some(boolean_node(!boolean(operand)?, call)),
+            _ => None
+        }
+    }
+
+    fn fold(&mut self, node : &Nodes) -> Nodes {
+        if let Nodes::Set(set) = node {
+            let elements = set.elements.iter()
+                .map(|element| self.fold(element))
+                .collect();
+            return Nodes::Set(ast::SetNode { elements, ..set.clone() });
+        }
+        if let Nodes::Vec(vec) = node {
+            let elements = vec.elements.iter()
+                .map(|element| self.fold(element))
+                .collect();
+            return Nodes::Vec(ast::VecNode { elements, ..vec.clone() });
+        }
+        if let Nodes::Tuple(tuple) = node {
+            let elements = tuple.elements.iter()
+                .map(|element| self.fold(element))
+                .collect();
+            return Nodes::Tuple(ast::TupleNode { elements, ..tuple.clone() });
+        }
+        if let Nodes::Interp(interp) = node {
+            let segments = interp.segments.iter()
+                .map(|segment| self.fold(segment))
+                .collect();
+            return Nodes::Interp(ast::InterpNode { segments, ..interp.clone() });
+        }
+        if let Nodes::Block(block) = node {
+            let statements = block.statements.iter()
+                .map(|statement| self.fold(statement))
+                .collect();
+            return Nodes::Block(ast::BlockNode { statements, ..block.clone() });
+        }
+        if let Nodes::Builder(builder) = node {
+            let generators = builder.generators.iter()
+                .map(|generator| ast::Generator {
+                    domain: self.fold(&generator.domain),
+                    ..generator.clone()
+                })
+                .collect();
+            return Nodes::Builder(ast::BuilderNode {
+                element: Box::new(self.fold(&builder.element)),
+                generators,
+                condition: builder.condition.as_ref()
+                    .map(|condition| Box::new(self.fold(condition))),
+                ..builder.clone()
+            });
+        }
+        if let Nodes::Call(call) = node {
+            if call.is_binary() {
+                let bin_op = call.callee.call().unwrap().callee.ident().unwrap();
+                let left  = self.fold(&call.callee.call().unwrap().operands[0]);
+                let right = self.fold(&call.operands[0]);
+
+                if let Some(folded) = self.binary(&bin_op.value, &left, &right, call) {
+                    self.folded = true;
+                    return folded;
+                }
+                return Nodes::Call(ast::CallNode {
+                    callee: Box::new(Nodes::Call(ast::CallNode {
+                        callee: Box::new(self.fold(&call.callee.call().unwrap().callee)),
+                        operands: vec![left],
+                        return_type: call.callee.yield_type(),
+                        parenthesised: false,
+                        site: call.callee.call().unwrap().site.clone()
+                    })),
+                    operands: vec![right],
+                    return_type: call.return_type.clone(),
+                    parenthesised: call.parenthesised,
+                    site: call.site.clone()
+                });
+            }
+            let operand = self.fold(&call.operands[0]);
+            if let Nodes::Ident(op) = &*call.callee {
+                if let Some(folded) = self.unary(&op.value, &operand, call) {
+                    self.folded = true;
+                    return folded;
+                }
+            }
+            return Nodes::Call(ast::CallNode {
+                callee: Box::new(self.fold(&call.callee)),
+                operands: vec![operand],
                 return_type: call.return_type.clone(),
+                parenthesised: call.parenthesised,
                 site: call.site.clone()
             });
+        }
+        return node.to_owned();
+    }
+}
 
-            let is_num_left  =  left.num().is_some();
-            let is_num_right = right.num().is_some();
-
-            if is_num_left && is_num_right {
-                let l_value =  left.num().unwrap().value;
-                let r_value = right.num().unwrap().value;
-                let value = match bin_op.value.as_str() {
-                    "+" => l_value + r_value,
-                    "-" => l_value - r_value,
-                    "*" => l_value * r_value,
-                    "/" => {
-                        if r_value == ast::Numerics::Natural(0) {
-                            return def;
-                        }
-                        l_value / r_value
-                    },
-                    _ => {
-                        return def;
-                    }
-                };
-                return Nodes::Num(ast::NumNode { value, site: call.site.clone() });
-            } else {
-                return def;
-            }
+/// Fold a branch until nothing more can be folded.  Only the issues
+/// of the last pass are reported, so that each is reported once.
+fn const_fold(node : &Nodes, filename : &str, issues : &mut Diagnostics) -> Nodes {
+    let mut node = node.to_owned();
+    loop {
+        let mut folder = Folder { filename, issues: Diagnostics::new(), folded: false };
+        node = folder.fold(&node);
+        if !folder.folded {
+            issues.append(&mut folder.issues);
+            return node;
         }
-        return Nodes::Call(ast::CallNode {
-            callee: Box::new(const_fold(&*call.callee)),
-            operands: vec![const_fold(&call.operands[0])],
-            return_type: call.return_type.clone(),
-            site: call.site.clone()
-        });
     }
-    return node.to_owned();
 }
 
 #[allow(non_upper_case_globals)]
-pub static default : fn(&Nodes) -> Nodes = const_fold;
+pub static default : fn(&Nodes, &str, &mut Diagnostics) -> Nodes = const_fold;
+
+#[cfg(test)]
+mod folding {
+    use crate::codes::{Code, V0037, V0038, V0039};
+    use crate::syntax::{self, ast::Nodes};
+
+    /// Right-hand side of the last assignment, and codes of any warnings.
+    fn fold(code : &str) -> (Nodes, Vec<Code>) {
+        let root = syntax::parse_source(code, "test.vh").ok().unwrap();
+        let assignment = root.branches.last().unwrap().call().unwrap();
+        let codes = root.issues.iter().map(|issue| issue.code).collect();
+        (assignment.operands[0].clone(), codes)
+    }
+
+    #[test]
+    fn exact_arithmetic_is_folded() {
+        let (value, codes) = fold("a : Rat\na = 1 / 3 + 18446744073709551615 * 2");
+        assert!(codes.is_empty());
+        assert_eq!(value.num().unwrap().value.to_string(), "110680464442257309691/3");
+    }
+
+    #[test]
+    fn nested_results_keep_folding() {
+        for (code, expected) in &[
+            ("a : Int\na = -(2 ^ 3 mod 5) * 4", "-12"),
+            ("a : Rat\na = (1 / 2) ^ (0 - 2) / 3", "4/3"),
+            ("a : Real\na = 7.5 mod 2", "1.5"),
+            ("a : Nat\na = 7 div 2", "3"),
+            ("a : Int\na = (0 - 7) div 2", "-3"),
+            ("a : Rat\na = (7 / 2) div (1 / 3)", "10"),
+        ] {
+            let (value, codes) = fold(code);
+            assert!(codes.is_empty());
+            assert_eq!(value.num().expect(code).value.to_string(), *expected);
+        }
+        for (code, expected) in &[
+            ("a : Sym\na = 1 / 2 < 0.75 and not (3 == 4)", "true"),
+            ("a : Sym\na = 2 >= 3 or :x /= :x", "false"),
+            ("a : Sym\na = \"ab\" ++ \"c\" == \"abc\"", "true"),
+        ] {
+            let (value, codes) = fold(code);
+            assert!(codes.is_empty());
+            assert_eq!(value.sym().expect(code).value, *expected);
+        }
+        let (value, _) = fold("a : String\na = \"con\" ++ \"cat\" ++ \"enate\"");
+        assert_eq!(value.str().unwrap().value, "concatenate");
+    }
+
+    #[test]
+    fn interpolations_are_folded() {
+        let (value, codes) = fold("a : String\na = \"half is :{1 / 2}\"");
+        assert!(codes.is_empty());
+        let segment = &value.interp().unwrap().segments[1];
+        assert_eq!(segment.num().unwrap().value.to_string(), "1/2");
+
+        let (_, codes) = fold("a : String\na = \"x is :{1 / 0}\"");
+        assert_eq!(codes, vec![V0037]);
+    }
+
+    #[test]
+    fn blocks_are_folded() {
+        let (value, codes) = fold("a : Rat\na = do:\n  b : Nat\n  b = 2 * 3\n  b / 4");
+        assert!(codes.is_empty());
+        let statements = &value.block().unwrap().statements;
+        let assignment = statements[1].call().unwrap();
+        assert_eq!(assignment.operands[0].num().unwrap().value.to_string(), "6");
+
+        let (_, codes) = fold("a : Rat\na = do: 1 / 0");
+        assert_eq!(codes, vec![V0037]);
+    }
+
+    #[test]
+    fn unrepresentable_results_are_not_folded() {
+        for (code, expected) in &[
+            ("a : Real\na = 1.0 / 0.0", V0037),
+            ("a : Rat\na = 2 / (3 - 3)", V0037),
+            ("a : Real\na = 1.0e300 * 1.0e300", V0038),
+            ("a : Real\na = 1.0e400 - 1.0e400", V0039),
+            ("a : Int\na = 0 ^ (0 - 1)", V0037),
+            ("a : Nat\na = 5 mod 0", V0037),
+            ("a : Nat\na = 5 div 0", V0037),
+        ] {
+            let (value, codes) = fold(code);
+            assert!(value.call().is_some(), "`{}' was folded.", code);
+            assert_eq!(codes, vec![*expected]);
+        }
+    }
+}
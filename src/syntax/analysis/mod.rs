@@ -5,6 +5,7 @@
 
 use std::collections::HashSet;
 use super::ast;
+use crate::issue::Diagnostics;
 
 mod type_resolver;
 mod type_balancer;
@@ -41,7 +42,11 @@ pub enum Transform {
     LOOP_UNROLLING
 }
 
-pub fn replace(root : &mut ast::Root, transforms : HashSet<Transform>) {
+/// Apply the given transformations to every branch of the tree,
+/// reporting any issues to `issues`.  A branch which cannot be
+/// resolved is reported and left as is.
+pub fn replace(root : &mut ast::Root, transforms : HashSet<Transform>,
+               issues : &mut Diagnostics) {
     let mut checker_context = type_checker::TypeChecker::new();
     let mut resolution_context = type_resolver::ResolutionContext::new();
 
@@ -51,15 +56,19 @@ pub fn replace(root : &mut ast::Root, transforms : HashSet<Transform>) {
 
     while i < length {
         if transforms.contains(&Transform::TYPE_RESOLUTION) {
-            let new = resolution_context.resolve_branch(&root.branches[i]);
-            root.branches[i] = new;
+            let resolved = resolution_context.resolve_branch(&root.branches[i]);
+            issues.append(&mut resolution_context.issues);
+            match resolved {
+                Ok(new) => root.branches[i] = new,
+                Err(issue) => issue.report(issues)
+            }
         }
         if transforms.contains(&Transform::TYPE_CHECKING) {
-            let new = checker_context.type_branch(&root.branches[i]);
+            let new = checker_context.type_branch(&root.branches[i], issues);
             root.branches[i] = new;
         }
         if transforms.contains(&Transform::CONSTANT_FOLDING) {
-            let new = constant_fold::default(&root.branches[i]);
+            let new = constant_fold::default(&root.branches[i], &root.filename, issues);
             root.branches[i] = new;
         }
         i += 1;
@@ -1,9 +1,10 @@
 use super::ast;
-use ast::Nodes;
+use ast::{Nodes, cast_strength};
 
 fn create_cast(node : &Nodes, cast : &ast::StaticTypes) -> Nodes {
     let to_type = match cast {
         ast::StaticTypes::TReal => ":Real",
+        ast::StaticTypes::TRational => ":Rat",
         ast::StaticTypes::TInteger => ":Int",
         ast::StaticTypes::TNatural => ":Nat",
         _ => panic!(".is_number() must be broken.")
@@ -22,21 +23,15 @@ fn create_cast(node : &Nodes, cast : &ast::StaticTypes) -> Nodes {
     cast_node
 }
 
-fn cast_strength(st : &ast::StaticTypes) -> i32 {
-    match st {
-        ast::StaticTypes::TReal    => 4,
-        ast::StaticTypes::TInteger => 2,
-        ast::StaticTypes::TNatural => 0,
-        _ => -1,
-    }
-}
-
 /// The type balancer is a static utility that checks if something
 /// like an arithmetic operator has unequal types (e.g. 4.3 + 6 (Real + Natural)).
 /// If it does, it balances the two sides of the expressions by injecting a type
 /// cast call to one of the arguments.
 /// We always cast up (without loss of information), so, 4.3 + 6 will cast the 6
 /// to be 6.0.    i.e. 4.3 + 6 ==> 4.3 + (cast 6 :Real) <=> 4.3 + 6.0.
+/// Division of naturals or integers is exact, so both sides are cast up
+/// to be rationals, i.e. 1 / 3 ==> (cast 1 :Rat) / (cast 3 :Rat), whereas
+/// `div' truncates the quotient, so it stays of the type of its operands.
 fn balance_types(node : &Nodes) -> Nodes {
     if let Nodes::Call(call) = node {
         if call.is_binary() {
@@ -46,7 +41,24 @@ fn balance_types(node : &Nodes) -> Nodes {
 
             let left_yield  =  left.yield_type();
             let right_yield = right.yield_type();
-            if ["+", "-", "*", "/"].contains(&bin_op.value.as_str()) {
+            let rational = ast::StaticTypes::TRational;
+            if bin_op.value == "/"
+            && left_yield.is_number() && right_yield.is_number()
+            && cast_strength(&left_yield).max(cast_strength(&right_yield))
+                < cast_strength(&rational) {
+                let mut new_call = ast::CallNode::new(
+                    ast::CallNode::new(
+                        *call.callee.call().unwrap().callee.clone(),
+                        vec![create_cast(&left, &rational)],
+                        call.callee.site()),
+                    vec![create_cast(&right, &rational)],
+                    call.site.clone());
+                if let Nodes::Call(ref mut c) = new_call {
+                    c.set_return_type(rational);
+                }
+                return new_call;
+            }
+            if ["+", "-", "*", "/", "^", "div", "mod"].contains(&bin_op.value.as_str()) {
                 if left_yield.is_number() && right_yield.is_number() {
                     if cast_strength(&left_yield) != cast_strength(&right_yield) {
 
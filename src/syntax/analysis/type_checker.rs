@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::issue;
+use issue::Diagnostics;
 
 use super::ast;
 use ast::Nodes;
@@ -21,7 +22,7 @@ impl TypeChecker {
         }
     }
 
-    pub fn type_branch(&mut self, node : &Nodes) -> Nodes {
+    pub fn type_branch(&mut self, node : &Nodes, issues : &mut Diagnostics) -> Nodes {
         let mut clone = node.to_owned();
         self.source_line = clone.location().line.unwrap();
         match clone {
@@ -36,6 +37,14 @@ impl TypeChecker {
                 }
                 return Nodes::Ident(i.to_owned());
             }
+            Nodes::Block(ref mut block) => {
+                // Annotations made within a block are only seen within it.
+                let mut block_checker = self.clone();
+                for statement in block.statements.iter_mut() {
+                    *statement = block_checker.type_branch(statement, issues);
+                }
+                return clone;
+            },
             Nodes::Call(ref mut call) => {
                 if let Nodes::Call(ref mut callee) = *call.callee {
                     if let Nodes::Ident(ref binary_ident) = *callee.callee {
@@ -44,7 +53,7 @@ impl TypeChecker {
                                 if let Nodes::Ident(ref mut annotatee) = callee.operands[0] {
                                     let annotation = (
                                         annotatee.value.to_owned(),
-                                        self.type_branch(&call.operands[0]).yield_type()
+                                        self.type_branch(&call.operands[0], issues).yield_type()
                                     );
 
                                     self.ident_map.insert(annotation.0.clone(), annotation.1.clone());
@@ -58,13 +67,13 @@ impl TypeChecker {
                                     return clone;
                                 } else {
                                     // Error: We need the left to be an ident.
-                                    issue!(ParseError,
+                                    issue!(ParseError, V0006,
                                         callee.operands[0].site().with_filename(&self.source_file),
                                         "The left side of the member-of operator (`:`), must be an identifier.
                                          You supplied a type of `{}'.
                                          Only variable names can be declared as being members of sets.",
                                         callee.operands[0].node_type())
-                                            .print();
+                                            .report(issues);
                                 }
                             },
                             "=" => {
@@ -89,12 +98,13 @@ impl TypeChecker {
 
                                         let base_node = operands.remove(0);
                                         if base_node.ident().is_none() {
-                                            issue!(ParseError,
+                                            issue!(ParseError, V0018,
                                                 base_node.site().with_filename(&self.source_file),
                                                 "Function definitions must have the defining function's base caller
                                                 be an identifier! You're trying to define a function that has
                                                 `{}' as base caller...", base_node.node_type())
-                                                    .print();
+                                                    .report(issues);
+                                            return node.to_owned();
                                         }
 
                                         let maybe_type = self.ident_map.get(&base_node.ident().unwrap().value);
@@ -103,12 +113,13 @@ impl TypeChecker {
                                                 println!("{}", base_node);
                                                 println!("{:?}", self.ident_map);
                                             }
-                                            issue!(TypeError,
+                                            issue!(TypeError, V0019,
                                                 base_node.site().with_filename(&self.source_file),
                                                 "Cannot find type annotation for the
                                                  function definition of `{}'.",
                                                  base_node.ident().unwrap().value)
-                                                    .print();
+                                                    .report(issues);
+                                            return node.to_owned();
                                         }
                                         let mut t = maybe_type.unwrap().clone();
 
@@ -123,7 +134,7 @@ impl TypeChecker {
                                             }
                                         }
 
-                                        call.operands[0] = func_checker.type_branch(&call.operands[0]);
+                                        call.operands[0] = func_checker.type_branch(&call.operands[0], issues);
                                         return clone;
                                     }
                                     Nodes::Ident(_assignee) => {
@@ -152,8 +163,8 @@ impl TypeChecker {
                 //  The call must have a yield of type `function` and the
                 //  input part of the function (input |-> output), must match
                 //  the type of the operand.  :^)
-                call.callee = Box::new(self.type_branch(&*call.callee));
-                call.operands = vec![self.type_branch(&call.operands[0])];
+                call.callee = Box::new(self.type_branch(&*call.callee, issues));
+                call.operands = vec![self.type_branch(&call.operands[0], issues)];
 
                 if let ast::StaticTypes::TFunction(_, o) = call.callee.yield_type() {
                     if let ast::StaticTypes::TSet(t) = *o {
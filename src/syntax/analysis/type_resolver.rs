@@ -5,11 +5,14 @@ use super::ast;
 use ast::{Nodes, StaticTypes};
 
 use super::type_balancer;
+use crate::syntax::operators::PrecedenceTable;
+use crate::suggest;
 
 use lazy_static::lazy_static;
 use std::collections::HashSet;
 
 use crate::issue;
+use issue::{Diagnostics, Fallible};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct SymbolEntry {
@@ -99,24 +102,52 @@ impl SymbolTable {
 
 pub struct ResolutionContext {
     table_chain : Vec<SymbolTable>,
-    filename : String
+    filename : String,
+    /// Issues found during resolution, collected by the caller.
+    pub issues : Diagnostics,
 }
 
 // TODO: Arithmetic operators will be properly defined
 // in some sort of prelude lib.
 lazy_static! {
     static ref INTERNAL_IDENTS : HashSet<String> = vec![
-        "=", ":", "->", "__raw_print", "+", "-", "*", "/", "^"
+        "=", ":", "->", "__raw_print", "+", "-", "*", "/", "^", "div", "mod",
+        "==", "/=", "<", "<=", ">", ">=", "and", "or", "not", "++",
+        "index"
     ].into_iter().map(String::from).collect();
 }
 
+/// Type common to all the elements of a set or vector literal.  Numbers of
+/// different types are all members of the strongest of them, and
+/// empty sets are members of any set of sets.  Otherwise, elements
+/// of differing types are of `Any' type.
+fn element_type(elements : &[Nodes]) -> StaticTypes {
+    let mut types = elements.iter().map(Nodes::yield_type);
+    let first = match types.next() {
+        Some(first) => first,
+        None => return StaticTypes::TNil
+    };
+    types.try_fold(first, |common, element| {
+        let empty = StaticTypes::TSet(Box::new(StaticTypes::TNil));
+        if common == element || element == empty && common.set_inner().is_some() {
+            Some(common)
+        } else if common == empty && element.set_inner().is_some() {
+            Some(element)
+        } else if common.is_number() && element.is_number() {
+            Some(if ast::cast_strength(&element) > ast::cast_strength(&common)
+                { element } else { common })
+        } else { None }
+    }).unwrap_or(StaticTypes::TUnknown)
+}
+
 // Rest is the implementation of the resolution context.
 impl ResolutionContext {
 
 pub fn new() -> Self {
      Self {
          table_chain: vec![SymbolTable::new("GLOBAL")],
-         filename: String::from("unspecified")
+         filename: String::from("unspecified"),
+         issues: Diagnostics::new(),
     }
 }
 
@@ -134,30 +165,156 @@ fn search_chain(&mut self, ident : &str) -> Option<&mut SymbolTable> {
     return None;
 }
 
-fn unwrap_set(&self, set : &StaticTypes) -> StaticTypes {
+/// Suggest a name in scope, an operator or a built-in set
+/// which may have been meant instead of an undeclared name.
+fn suggest_ident(&self, name : &str) -> Option<String> {
+    let operators = PrecedenceTable::new();
+    let in_scope = self.table_chain.iter().rev()
+        .flat_map(|table| table.iter())
+        .map(|entry| entry.identifier.as_str());
+    suggest::did_you_mean(name, in_scope
+        .chain(operators.table.iter().map(|op| op.name))
+        .chain(ast::BUILTIN_SETS.iter().copied()))
+}
+
+fn unwrap_set(&self, set : &StaticTypes) -> Fallible<StaticTypes> {
     if let StaticTypes::TSet(internal) = set {
-        *internal.clone()
+        Ok(*internal.clone())
     } else {
         // We should never get here, we should always have
         // checked earlier if a function signature tries to map
         // between non-sets.
         use crate::site::Site;
-        issue!(TypeError, Site::new().with_filename(&self.filename),
-            "Cannot create mapping (function) between non-sets.")
-            .crash_and_burn()
+        Err(issue!(TypeError, V0015, Site::new().with_filename(&self.filename),
+            "Cannot create mapping (function) between non-sets."))
     }
 }
 
+/// Type of the element at `index' of a tuple (or vector), if it is known.
+fn index_type(&mut self, index : &Nodes, indexed : &Nodes) -> StaticTypes {
+    let index_type = index.yield_type();
+    if index_type != StaticTypes::TNatural {
+        issue!(TypeError, V0044, index.site().with_filename(&self.filename),
+            "Index must be a natural, instead got type of `{}'.", index_type)
+                .report(&mut self.issues);
+        return StaticTypes::TUnknown;
+    }
+    let position = match index.num().map(|num| &num.value) {
+        Some(ast::Numerics::Natural(i)) => Some(*i),
+        _ => None
+    };
+    let (length, element) = match indexed.yield_type() {
+        StaticTypes::TVector(element, length) => (length, Some(*element)),
+        StaticTypes::TTuple(factors) =>
+            (factors.len(), position.and_then(|i| factors.get(i).cloned())),
+        StaticTypes::TUnknown => return StaticTypes::TUnknown,
+        indexed_type => {
+            issue!(TypeError, V0044, indexed.site().with_filename(&self.filename),
+                "Cannot index a value of type `{}'.", indexed_type)
+                    .note("Only tuples and vectors may be indexed.")
+                    .report(&mut self.issues);
+            return StaticTypes::TUnknown;
+        }
+    };
+    if let Some(i) = position.filter(|i| *i >= length) {
+        issue!(TypeError, V0043, index.site().with_filename(&self.filename),
+            "Index {} is out of range, as there are only {} elements.", i, length)
+                .note("Elements are indexed from zero.")
+                .report(&mut self.issues);
+        return StaticTypes::TUnknown;
+    }
+    // The type of a tuple's element depends on which it is.
+    element.unwrap_or(StaticTypes::TUnknown)
+}
+
+/// Adds the variables bound by a pattern to the `scope' of the function
+/// being defined, where the pattern matches an argument of type `argument'.
+fn resolve_pattern(&mut self, pattern : &Nodes, argument : &StaticTypes,
+                   scope : &mut SymbolTable) -> Fallible<()> {
+    let site = pattern.site().with_filename(&self.filename);
+    let pattern_type = match pattern {
+        Nodes::Ident(ident) if ident.is_wildcard() => return Ok(()),
+        Nodes::Ident(ident) if !ident.is_constructor() => {
+            if scope.contains(&ident.value) {
+                return Err(issue!(TypeError, V0047, site,
+                    "Variable `{}' is bound more than once by this clause.", ident.value)
+                    .note("To match equal arguments, bind them to different \
+                           variables, and compare them in the body."));
+            }
+            scope.push(&ident.value, argument.clone(), true);
+            return Ok(());
+        },
+        Nodes::Tuple(tuple) => {
+            let factors = match argument {
+                StaticTypes::TTuple(factors) => factors.clone(),
+                StaticTypes::TVector(element, length) => vec![*element.clone(); *length],
+                StaticTypes::TUnknown => vec![StaticTypes::TUnknown; tuple.elements.len()],
+                _ => vec![]
+            };
+            if factors.len() != tuple.elements.len() {
+                return Err(issue!(TypeError, V0046, site,
+                    "Tuple of {} elements cannot match an argument of type `{}'.",
+                    tuple.elements.len(), argument));
+            }
+            for (element, factor) in tuple.elements.iter().zip(&factors) {
+                self.resolve_pattern(element, factor, scope)?;
+            }
+            return Ok(());
+        },
+        // A constructor applied to patterns, e.g. `Succ n'.
+        Nodes::Call(call) if call.base_call().ident()
+                                 .is_some_and(ast::IdentNode::is_constructor) => {
+            let mut parts = call.collect();
+            let constructor = self.resolve_branch(&parts.remove(0))?;
+            let mut constructor_type = constructor.yield_type();
+            for part in &parts {
+                let (domain, codomain) = match &constructor_type {
+                    StaticTypes::TFunction(domain, codomain) =>
+                        (self.unwrap_set(domain)?, self.unwrap_set(codomain)?),
+                    StaticTypes::TUnknown => (StaticTypes::TUnknown, StaticTypes::TUnknown),
+                    _ => return Err(issue!(TypeError, V0046,
+                        part.site().with_filename(&self.filename),
+                        "Constructor `{}' is applied to too many patterns.",
+                        constructor.ident().unwrap().value)
+                        .note(&format!("`{}' has type of `{}'.",
+                            constructor.ident().unwrap().value, constructor.yield_type())))
+                };
+                self.resolve_pattern(part, &domain, scope)?;
+                constructor_type = codomain;
+            }
+            constructor_type
+        },
+        // Constants and literals, e.g. `Zero', `1', `-1' or `"a"'.
+        Nodes::Ident(_) | Nodes::Num(_) | Nodes::Str(_) | Nodes::Sym(_) | Nodes::Nil(_) =>
+            self.resolve_branch(pattern)?.yield_type(),
+        Nodes::Call(call) if call.is_unary() && call.operands[0].num().is_some()
+                          && call.callee.ident().is_some_and(|op| op.value == "-") =>
+            self.resolve_branch(pattern)?.yield_type(),
+        _ => return Err(issue!(ParseError, V0045, site,
+            "Cannot match against this {}.", pattern.node_type())
+            .note("Patterns are literals, variables, `_', constructors, \
+                   or tuples of patterns."))
+    };
+    if pattern_type != *argument
+    && pattern_type != StaticTypes::TUnknown
+    && *argument != StaticTypes::TUnknown {
+        return Err(issue!(TypeError, V0046, site,
+            "Pattern of type `{}' cannot match an argument of type `{}'.",
+            pattern_type, argument));
+    }
+    Ok(())
+}
+
 /// # Terminology
 /// `appl_0` - refers to the the 0th (base) application (call).
 /// `appl_n` - refers to any nested application n-levels deep.
 /// # Function
 /// Entry point for type resolution of AST branches.
 /// Returns a clone of the branch but with added type-information.
-pub fn resolve_branch(&mut self, branch : &Nodes) -> Nodes {
+pub fn resolve_branch(&mut self, branch : &Nodes) -> Fallible<Nodes> {
     if let Nodes::File(file_node) = branch {
         self.filename = file_node.filename.to_owned();
-        return branch.to_owned();
+        return Ok(branch.to_owned());
     }
 
     let mut node = branch.to_owned();
@@ -168,7 +325,11 @@ pub fn resolve_branch(&mut self, branch : &Nodes) -> Nodes {
     if let Nodes::Ident(ref mut ident) = node {
         // Ignore certain variables (internals, not user declared).
         if INTERNAL_IDENTS.contains(&ident.value) {
-            return node;
+            return Ok(node);
+        }
+        // Built-in sets (`Nat', `Real', etc.) are always declared.
+        if branch.yield_type() != StaticTypes::TUnknown {
+            return Ok(node);
         }
 
         // Search for variable in tables, to give it a type.
@@ -178,23 +339,99 @@ pub fn resolve_branch(&mut self, branch : &Nodes) -> Nodes {
             let signatures = table.collect_signatures(&ident.value);
             if signatures.len() > 1 {
                 // TODO: Partial application not considered.
-                issue!(ParseError,
+                issue!(ParseError, V0009,
                     ident.site.with_filename(&self.filename),
                     "Variable has multiple type signatures. Overloading \
                     types is only possible with functions.")
-                        .print();
+                        .report(&mut self.issues);
             }
-            // We can unwrap this because we know it contains exactly
-            // one (1) element.
+            // We can unwrap this because we know it contains
+            // at least one (1) element.
             let signature = signatures.iter().next().unwrap();
             // Give the identifier it's signature.
             ident.static_type = signature.clone();
         } else { // Variable has not been declared.
-            issue!(ParseError,
+            let mut issue = issue!(ParseError, V0010,
                 ident.site.with_filename(&self.filename),
                 "Variable `{}' is used, but has not been declared.",
                 &ident.value);
+            if let Some(note) = self.suggest_ident(&ident.value) {
+                issue = issue.note(&note);
+            }
+            issue.report(&mut self.issues);
+        }
+    } else if let Nodes::Set(ref mut set) = node {
+        for element in set.elements.iter_mut() {
+            *element = self.resolve_branch(element)?;
         }
+        set.element_type = element_type(&set.elements);
+    } else if let Nodes::Vec(ref mut vec) = node {
+        for element in vec.elements.iter_mut() {
+            *element = self.resolve_branch(element)?;
+        }
+        vec.element_type = element_type(&vec.elements);
+    } else if let Nodes::Tuple(ref mut tuple) = node {
+        for element in tuple.elements.iter_mut() {
+            *element = self.resolve_branch(element)?;
+        }
+    // Variables of a set-builder are bound only within it.
+    } else if let Nodes::Builder(ref mut builder) = node {
+        let mut builder_scope = SymbolTable::new("<set-builder>");
+        for generator in builder.generators.iter_mut() {
+            generator.domain = self.resolve_branch(&generator.domain)?;
+            let domain_type = generator.domain.yield_type();
+            let member_type = match domain_type.set_inner() {
+                Some(member_type) => member_type,
+                None => {
+                    issue!(TypeError, V0042,
+                        generator.domain.site().with_filename(&self.filename),
+                        "`{}' must be drawn from a set, instead got type of `{}'.",
+                        generator.variable.value, domain_type)
+                        .report(&mut self.issues);
+                    StaticTypes::TUnknown
+                }
+            };
+            generator.variable.static_type = member_type.clone();
+            builder_scope.push(&generator.variable.value, member_type, true);
+        }
+        self.table_chain.push(builder_scope);
+        let element = self.resolve_branch(&builder.element);
+        let condition = builder.condition.as_ref()
+            .map(|condition| self.resolve_branch(condition)).transpose();
+        self.table_chain.pop();
+
+        *builder.element = element?;
+        builder.condition = condition?.map(Box::new);
+        builder.element_type = builder.element.yield_type();
+    // Embedded expressions must be strings, or be shown as strings.
+    } else if let Nodes::Interp(ref mut interp) = node {
+        for segment in interp.segments.iter_mut() {
+            *segment = self.resolve_branch(segment)?;
+            let segment_type = segment.yield_type();
+            if segment_type != StaticTypes::TString && !segment_type.is_number() {
+                issue!(TypeError, V0034,
+                    segment.site().with_filename(&self.filename),
+                    "Cannot interpolate a value of type `{}' into a string.",
+                    segment_type)
+                        .note("Only strings, naturals, integers and \
+                               reals may be embedded in a string.")
+                        .report(&mut self.issues);
+            }
+        }
+    // Variables bound in a `do' block are only seen within it.
+    } else if let Nodes::Block(ref mut block) = node {
+        if block.keyword != "do" {
+            return Err(issue!(CompError, V0048,
+                block.site.with_filename(&self.filename),
+                "Blocks opened by `{}:' cannot be compiled yet.", block.keyword)
+                .note("Only `do:' blocks are supported."));
+        }
+        self.table_chain.push(SymbolTable::new("<do-block>"));
+        let statements : Fallible<Vec<Nodes>> = block.statements.iter()
+            .map(|statement| self.resolve_branch(statement))
+            .collect();
+        self.table_chain.pop();
+        block.statements = statements?;
     // What to do, if we have a call to resolve.
     } else if let Nodes::Call(ref mut appl_0) = node {
         let appl_0_clone = appl_0.clone();
@@ -220,42 +457,88 @@ pub fn resolve_branch(&mut self, branch : &Nodes) -> Nodes {
                     // Pattern matching on signatures is also allowed:
                     // `f (n : Nat) = n + 2`, which matches on n that is natural.
 
-                    return node;
+                    return Ok(node);
                 },
                 "=" => {
-                    *appl_0 = self.resolve_assignment(appl_0_clone, appl_1.clone());
+                    *appl_0 = self.resolve_assignment(appl_0_clone, appl_1.clone())?;
                     skip_type_check = true;
                 },
                 // Internal functions, with internal
                 // types/definitions, etc.
                   "+" | "-"
                 | "*" | "/"
-                | "^" => {  // Arithmetic operations typing.
+                | "^" | "div" | "mod" => {  // Arithmetic operations typing.
                     // Resolve on both sides as much as possible.
                     if let Some(operand) = appl_0_clone.operand() {
-                        appl_0.operands[0] = self.resolve_branch(operand);
+                        appl_0.operands[0] = self.resolve_branch(operand)?;
                     }
                     if let Some(operand) = appl_1.operand() {
-                        appl_1.operands[0] = self.resolve_branch(operand);
+                        appl_1.operands[0] = self.resolve_branch(operand)?;
                     }
                     let cloned_node = node.clone();
                     // This HAS to be rewritten.
-                    return type_balancer::default(&cloned_node);
+                    return Ok(type_balancer::default(&cloned_node));
+                }
+                // Comparisons and logical operations give booleans,
+                //  i.e. `:true' or `:false', and `++' joins strings.
+                  "==" | "/="
+                | "<"  | "<="
+                | ">"  | ">="
+                | "and" | "or"
+                | "++" => {
+                    if let Some(operand) = appl_0_clone.operand() {
+                        appl_0.operands[0] = self.resolve_branch(operand)?;
+                    }
+                    if let Some(operand) = appl_1.operand() {
+                        appl_1.operands[0] = self.resolve_branch(operand)?;
+                    }
+                    appl_0.return_type = if ident_1.value == "++" {
+                        StaticTypes::TString
+                    } else { StaticTypes::TSymbol };
+                    return Ok(node);
+                }
+                // Elements of tuples, i.e. `index i p' or `p[i]'.
+                "index" => {
+                    if let Some(operand) = appl_0_clone.operand() {
+                        appl_0.operands[0] = self.resolve_branch(operand)?;
+                    }
+                    if let Some(operand) = appl_1.operand() {
+                        appl_1.operands[0] = self.resolve_branch(operand)?;
+                    }
+                    appl_0.return_type = self.index_type(&appl_1.operands[0],
+                                                         &appl_0.operands[0]);
+                    return Ok(node);
                 }
                 _ => ()
             }
         }}
+        // Unary operations, `not' gives a boolean, and negation
+        //  gives a number of the same type, although naturals
+        //  become integers.
+        if let Nodes::Ident(ref ident_0) = *appl_0.callee {
+            if ident_0.value == "not" || ident_0.value == "-" {
+                let operand = appl_0.operands[0].clone();
+                appl_0.operands[0] = self.resolve_branch(&operand)?;
+                let operand_type = appl_0.operands[0].yield_type();
+                appl_0.return_type = match ident_0.value.as_str() {
+                    "not" => StaticTypes::TSymbol,
+                    _ if operand_type == StaticTypes::TNatural => StaticTypes::TInteger,
+                    _ => operand_type
+                };
+                return Ok(node);
+            }
+        }
         // Any call should resolve its callee type, and check if it is legal
         // to apply an operand of such a (resolved) type.
         // This entire call expression must thus also be typed, unrolling
         // the type from the callee.
         if skip_type_check {
-            return node;
+            return Ok(node);
         }
         // Recursively resolve both sides of the expression.
-        appl_0.callee = Box::new(self.resolve_branch(&*appl_0.callee));
+        appl_0.callee = Box::new(self.resolve_branch(&*appl_0.callee)?);
         if let Some(operand) = appl_0.operand() {
-            appl_0.operands[0] = self.resolve_branch(operand);
+            appl_0.operands[0] = self.resolve_branch(operand)?;
         }
         // Check application is legal.
         let appl_0_st = (*appl_0.callee).yield_type().to_owned();
@@ -265,12 +548,11 @@ pub fn resolve_branch(&mut self, branch : &Nodes) -> Nodes {
             let maybe_op_inner_type = (*box_op_t).set_inner();
 
             if maybe_op_inner_type.is_none() {
-                // Fatal, we should really never get here,
+                // We should really never get here,
                 // because we _should_ check for this earlier.
-                fatal!(TypeError,
+                return Err(issue!(TypeError, V0013,
                     (*appl_0.callee).site().with_filename(&self.filename),
-                    "Function should map from a set, it does not.")
-                    .print();
+                    "Function should map from a set, it does not."));
             }
 
             // Safe to unwrap, we've checked for none.
@@ -285,13 +567,13 @@ pub fn resolve_branch(&mut self, branch : &Nodes) -> Nodes {
                 // example.
                 // We should also emit a warning (always?) when
                 // an implicit cast has taken place.
-                issue!(TypeError,
+                issue!(TypeError, V0012,
                     appl_0.operands[0].site().with_filename(&self.filename),
                     "Mismatching type in function call.
                      Expected argument of element \
                      of `{}', instead got a `{}'.",
                     op_inner_type, op_0_st)
-                        .print();
+                        .report(&mut self.issues);
             }
             // If so, we can continue to unroll the type and
             // assign it to this expression.
@@ -300,28 +582,27 @@ pub fn resolve_branch(&mut self, branch : &Nodes) -> Nodes {
             // a type of element of box_ret_t.
             let return_type = (*box_ret_t).set_inner();
             if return_type.is_none() {
-                // Fatal, see similar comment above.
-                issue!(TypeError,
+                // See similar comment above.
+                return Err(issue!(TypeError, V0014,
                     (*appl_0.callee).site().with_filename(&self.filename),
-                    "Function should map to a set, it does not.")
-                        .print();
+                    "Function should map to a set, it does not."));
             }
             appl_0.return_type = return_type.unwrap().clone();
         } else {
-            issue!(TypeError,
+            issue!(TypeError, V0011,
                 appl_0.callee.site().with_filename(&self.filename),
                 "Function-application / juxtaposition is not \
                  defined on type of `{}'.", appl_0_st)
-                    .print();
+                    .report(&mut self.issues);
         }
     }
 
-    node
+    Ok(node)
 }
 
 fn resolve_assignment(&mut self,
                       mut appl_0 : ast::CallNode,
-                      appl_1 : ast::CallNode) -> ast::CallNode {
+                      appl_1 : ast::CallNode) -> Fallible<ast::CallNode> {
     // TODO: Assignment means implicit type
     // is given, if no type signature found,
     // OR, it means we are defining a declared
@@ -356,7 +637,7 @@ fn resolve_assignment(&mut self,
     // Handle variable (identifier) assignment:
     if let Nodes::Ident(ident_op_1) = lhs {
         // Recursively resolve RHS of assignment.
-        appl_0.operands[0] = self.resolve_branch(&appl_0.operands[0]);
+        appl_0.operands[0] = self.resolve_branch(&appl_0.operands[0])?;
         // Check if an signature exists.
         let maybe_table = self.search_chain(&ident_op_1.value);
         if let Some(table) = maybe_table {
@@ -369,7 +650,7 @@ fn resolve_assignment(&mut self,
 
             // Search did not give `None`, so entries
             // should never be empty!
-            assert!(entries.len() > 0);
+            assert!(!entries.is_empty());
             #[cfg(feature="debug")] {
                 println!("Assignment of `{}':", ident_op_1.value);
                 println!("- RHS-type: {}", appl_0.operands[0].yield_type());
@@ -377,27 +658,52 @@ fn resolve_assignment(&mut self,
             }
 
             if entries.len() == 1 { // Not overloaded.
-                let ref mut entry = entries[0];
+                let entry = &mut entries[0];
                 // Check entry matches type of RHS
                 // of assignment.
 
+                // The empty set is a member of every set of sets,
+                //  and the empty vector of every set of empty vectors.
+                match (&mut appl_0.operands[0], &entry.signature) {
+                    (Nodes::Set(set), signature) if set.elements.is_empty() => {
+                        if let Some(inner) = signature.set_inner() {
+                            set.element_type = inner;
+                        }
+                    },
+                    (Nodes::Vec(vec), StaticTypes::TVector(inner, 0)) if vec.elements.is_empty() =>
+                        vec.element_type = *inner.clone(),
+                    _ => ()
+                }
                 // TODO: Check if types can be coerced.
                 let rhs_type = appl_0.operands[0].yield_type();
-                if rhs_type != entry.signature {
+                let mismatch = if rhs_type != entry.signature {
                     // TODO: Can cast? if so, do
                     // and don't throw an error.
-                    issue!(TypeError,
+                    let issue = issue!(TypeError, V0016,
                         appl_0.operands[0].site().with_filename(filename),
                         "Signature does not match \
                          right-hand-side of assignment.
                          Expected `{}', got `{}'.",
-                        entry.signature, rhs_type)
-                            .print();
-                }
+                        entry.signature, rhs_type);
+                    // Division is exact, unless asked to truncate.
+                    let divided = appl_0.operands[0].call()
+                        .filter(|call| call.is_binary())
+                        .and_then(|call| call.callee.call()?.callee.ident())
+                        .is_some_and(|op| op.value == "/");
+                    Some(match entry.signature {
+                        StaticTypes::TNatural
+                        | StaticTypes::TInteger if divided => issue.note(
+                            "Use `div' for division which truncates to a whole number."),
+                        _ => issue
+                    })
+                } else { None };
                 // Otherwise, all is fine,
                 // and we can update whether it has
                 // been defined.
                 entry.was_defined();
+                if let Some(issue) = mismatch {
+                    issue.report(&mut self.issues);
+                }
             } else { // Overloaded.
                 // TODO: Check if it is valid to overload
                 // here. Non-functions cannot be overloaded
@@ -410,14 +716,13 @@ fn resolve_assignment(&mut self,
     } else if let Nodes::Call(call_op_1) = lhs {
         let base_call = call_op_1.base_call();
         if !base_call.is_ident() {
-            // Fatal, we must define the call on some sort of ident.
-            fatal!(ParseError,
+            // We must define the call on some sort of ident.
+            return Err(issue!(ParseError, V0018,
                 base_call.site().with_filename(&self.filename),
                 "You have to assign to a call on an identifier,
                  this identifier is the function you are defining.")
                 .note(&format!("Expected an `identifier', found `{}'!",
-                    base_call.node_type()))
-                .print();
+                    base_call.node_type())));
         }
         // We've checked, and we may unwrap it.
         let base_call = base_call.ident().unwrap();
@@ -440,83 +745,74 @@ fn resolve_assignment(&mut self,
             func_type = StaticTypes::TUnknown;  // FIXME.
         }
 
-        let mut left_type  = StaticTypes::TUnknown;
-        let mut right_type = StaticTypes::TUnknown;
         // Check if we do actually have a function type.
-        if let StaticTypes::TFunction(l, r) = func_type {
-            left_type  = self.unwrap_set(&*l);  // This should have already been
-            right_type = self.unwrap_set(&*r); // checked for.
-        } else { // Fatal, needs to be a function.
-            fatal!(TypeError, call_op_1.site.with_filename(&self.filename),
+        let (mut left_type, mut right_type) = if let StaticTypes::TFunction(l, r) = func_type {
+            (self.unwrap_set(&l)?,   // This should have already been
+             self.unwrap_set(&r)?)  // checked for.
+        } else { // Needs to be a function.
+            return Err(issue!(TypeError, V0020, call_op_1.site.with_filename(&self.filename),
                 "Trying to define a function on a variable that does \
                  not have type of `function'.")
                 .note(&format!("`{}' has type of `{}', which is not a function.",
-                    base_call.value, func_type))
-                .print();
-        }
+                    base_call.value, func_type)));
+        };
 
         let lhs_operands = call_op_1.collect_operands();
         let operand_count = lhs_operands.len();
         let mut function_scope = SymbolTable::new(&base_call.value);
         for (i, lhs_operand) in lhs_operands.iter().enumerate() {
-            if let Nodes::Ident(lhs_op_ident) = lhs_operand {
-                function_scope.push(&lhs_op_ident.value, left_type.clone(), true);
-                if i == operand_count - 1 {
-                    break;  // No need to disect any further.
-                }
+            // Arguments are patterns, the simplest of which are variables.
+            self.resolve_pattern(lhs_operand, &left_type, &mut function_scope)?;
+            if i == operand_count - 1 {
+                break;  // No need to disect any further.
+            }
 
-                if let StaticTypes::TFunction(l, r) = right_type {
-                    left_type  = self.unwrap_set(&*l);
-                    right_type = self.unwrap_set(&*r);
-                } else {
-                    fatal!(TypeError,
-                        lhs_operands
-                            .last().unwrap()
-                            .site().with_filename(&self.filename),
-                        "Function definition provided with too many arguments.
-                         The type signature disagrees with the number
-                         of arguments you have provided.")
-                        .note("Consider removing this, or altering \
-                               the type signature.")
-                        .print();
-                }
+            if let StaticTypes::TFunction(l, r) = right_type {
+                left_type  = self.unwrap_set(&l)?;
+                right_type = self.unwrap_set(&r)?;
             } else {
-                // TODO: Not an ident, that means we're
-                // pattern matching.  This will need a general
-                // implementation in the future.
+                return Err(issue!(TypeError, V0021,
+                    lhs_operands
+                        .last().unwrap()
+                        .site().with_filename(&self.filename),
+                    "Function definition provided with too many arguments.
+                     The type signature disagrees with the number
+                     of arguments you have provided.")
+                    .note("Consider removing this, or altering \
+                           the type signature."));
             }
         }
         // Now the function scope is populated with the arguments.
         self.table_chain.push(function_scope); // Add the scope to the stack.
         // Type the right side of the equality:
-        let typed_rhs = self.resolve_branch(&rhs);
+        let typed_rhs = self.resolve_branch(&rhs)?;
         // Check if the RHS has the correct type.
         if typed_rhs.yield_type() == right_type {
             appl_0.operands[0] = typed_rhs;
         } else {
             // TODO: If the the types disagree, but the type is
             // a subset, just cast the type.  For now, it's only an error:
-            issue!(TypeError, rhs.site().with_filename(&self.filename),
+            issue!(TypeError, V0022, rhs.site().with_filename(&self.filename),
                 "Right hand side of function definition does not agree \
                  with type signature.
                  Expected type of `{}', got `{}'.",
                 &right_type, &typed_rhs.yield_type())
                 .note("Either convert the value, or alter the type signature.")
-                .print();
+                .report(&mut self.issues);
         }
         // The function scope is no longer in use.
         self.table_chain.pop();
     } else {
         // TODO: Pattern matching etc.
 
-        issue!(ParseError,
+        issue!(ParseError, V0017,
             appl_1.operands[0].site().with_filename(&self.filename),
             "Cannot assign to `{}' structure.",
             appl_1.operands[0].node_type())
-                .print();
+                .report(&mut self.issues);
     }
 
-    return appl_0;
+    return Ok(appl_0);
 }
 
 fn resolve_annotation(&mut self, appl_0 : ast::CallNode, appl_1 : ast::CallNode) {
@@ -529,25 +825,31 @@ fn resolve_annotation(&mut self, appl_0 : ast::CallNode, appl_1 : ast::CallNode)
                 self.current_table().push(
                     &op_id_1.value, *signature, false);
             } else {
-                issue!(TypeError,
+                let mut issue = issue!(TypeError, V0008,
                     op_0.site().with_filename(&self.filename),
                     "Right of type annotation must be a set; \
-                     instead got type of `{}'.", set_signature)
-                        .print();
+                     instead got type of `{}'.", set_signature);
+                let note = op_0.ident().and_then(|ident|
+                    suggest::did_you_mean(&ident.value,
+                        ast::BUILTIN_SETS.iter().copied()));
+                if let Some(note) = note {
+                    issue = issue.note(&note);
+                }
+                issue.report(&mut self.issues);
             }
         } else {
-            issue!(ParseError,
+            issue!(ParseError, V0006,
                 op_1.site().with_filename(&self.filename),
                 "Left of `:` type annotator must be \
                  an identifier; found `{}'.", op_1.node_type())
                     .note("Has to be a variable.")
-                    .print();
+                    .report(&mut self.issues);
         }
     } else {
-        issue!(ParseError,
+        issue!(ParseError, V0007,
             appl_1.site.with_filename(&self.filename),
             "No expression found left of `:`.")
-                .print();
+                .report(&mut self.issues);
     }
 }
 }
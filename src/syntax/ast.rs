@@ -1,7 +1,15 @@
 use std::{fmt, ops};
+use std::cmp::Ordering;
 use std::collections::VecDeque;
+use std::convert::TryFrom;
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_rational::BigRational;
+use num_traits::{Num, ToPrimitive};
 
 use crate::site::{Site, Location};
+use crate::issue::Diagnostics;
+use crate::lints::LintLevels;
 
 /// Identifiers, node representing a name that
 /// will represent a value stored.
@@ -18,52 +26,227 @@ pub struct IdentNode {
 }
 
 /// Different types of possible number types in the language.
-/// Max size is determined by max pointer size.
-#[derive(PartialEq, Clone, Copy, Debug)]
+/// Numbers which fit into a pointer-sized integer are stored as such,
+/// any larger (or smaller) are promoted to arbitrary-precision integers.
+#[derive(PartialEq, Clone, Debug)]
 pub enum Numerics {
     /// Naturals are unsigned ints.
     Natural(usize),
     /// Integers are signed.
     Integer(isize),
+    /// Naturals too large to fit in a `usize'.
+    BigNatural(BigUint),
+    /// Integers too large (or small) to fit in an `isize'.
+    BigInteger(BigInt),
+    /// Rationals are exact fractions, always in lowest terms.
+    Rational(BigRational),
     /// Reals are represented as a double.
     Real(f64)
 }
 
-fn strongest_cast(left : Numerics, right : Numerics) -> StaticTypes {
-    let mut cast = StaticTypes::TNatural;
-    match left {
-        Numerics::Real(_) => cast = StaticTypes::TReal,
-        Numerics::Integer(_) => cast = StaticTypes::TInteger,
-        _ => ()
-    };
-    if cast == StaticTypes::TReal { return cast; }
-    match right {
-        Numerics::Real(_) => cast = StaticTypes::TReal,
-        Numerics::Integer(_) => cast = StaticTypes::TInteger,
-        _ => ()
-    };
-    cast
+impl Numerics {
+    /// Exact value of a natural or integer, `None' for reals.
+    fn exact(&self) -> Option<BigInt> {
+        match self {
+            Numerics::Natural(n)    => Some(BigInt::from(*n)),
+            Numerics::Integer(n)    => Some(BigInt::from(*n)),
+            Numerics::BigNatural(n) => Some(BigInt::from(n.clone())),
+            Numerics::BigInteger(n) => Some(n.clone()),
+            Numerics::Rational(_)
+            | Numerics::Real(_) => None
+        }
+    }
+
+    /// Exact value of any number other than a real.
+    fn fraction(&self) -> Option<BigRational> {
+        match self {
+            Numerics::Rational(q) => Some(q.clone()),
+            _ => self.exact().map(BigRational::from_integer)
+        }
+    }
+
+    /// Nearest double, big numbers out of range become infinite.
+    fn approximate(&self) -> f64 {
+        match self {
+            Numerics::Natural(n)    => *n as f64,
+            Numerics::Integer(n)    => *n as f64,
+            Numerics::BigNatural(n) => n.to_f64().unwrap_or(f64::INFINITY),
+            Numerics::BigInteger(n) => n.to_f64().unwrap_or(match n.sign() {
+                Sign::Minus => f64::NEG_INFINITY,
+                _ => f64::INFINITY
+            }),
+            Numerics::Rational(q) => q.to_f64().unwrap_or(f64::NAN),
+            Numerics::Real(n) => *n
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Numerics::Real(n) => *n == 0f64,
+            _ => self.fraction().is_some_and(|q| q.numer().sign() == Sign::NoSign)
+        }
+    }
+
+    /// Cast a number up to a stronger numeric type, without any loss
+    /// of information.  `None' if the cast could lose information.
+    pub fn cast(&self, to : &StaticTypes) -> Option<Numerics> {
+        let from = numeric_type(self);
+        if from == *to {
+            return Some(self.clone());
+        }
+        if cast_strength(&from) > cast_strength(to) {
+            return None;
+        }
+        Some(match to {
+            StaticTypes::TInteger => {
+                let n = self.exact()?;
+                match isize::try_from(&n) {
+                    Ok(i)  => Numerics::Integer(i),
+                    Err(_) => Numerics::BigInteger(n)
+                }
+            },
+            StaticTypes::TRational => Numerics::Rational(self.fraction()?),
+            StaticTypes::TReal => Numerics::Real(self.approximate()),
+            _ => return None
+        })
+    }
+
+    /// Raise to a power.  `None' if the result cannot be computed exactly,
+    /// e.g. a natural to a negative power, or would be unreasonably large.
+    pub fn pow(&self, exponent : &Numerics) -> Option<Numerics> {
+        let cast = strongest_cast(self, exponent);
+        if cast == StaticTypes::TReal {
+            return Some(Numerics::Real(self.approximate().powf(exponent.approximate())));
+        }
+        let power = exponent.fraction()?;
+        if !power.is_integer() {
+            return None;
+        }
+        let power = power.to_integer();
+        let n = u32::try_from(power.magnitude()).ok()?;
+        let base = self.fraction()?;
+        let bits = base.numer().bits().max(base.denom().bits());
+        if bits.saturating_mul(n as u64) > MAX_POWER_BITS {
+            return None;
+        }
+        let result = BigRational::new(base.numer().pow(n), base.denom().pow(n));
+        if cast == StaticTypes::TRational {
+            if power.sign() == Sign::Minus {
+                if result.numer().sign() == Sign::NoSign { return None; }
+                return Some(Numerics::Rational(result.recip()));
+            }
+            return Some(Numerics::Rational(result));
+        }
+        if power.sign() == Sign::Minus {
+            return None;
+        }
+        Some(integral(result.to_integer(), cast))
+    }
+
+    /// Truncating division, the quotient rounded towards zero.
+    pub fn truncated_div(&self, divisor : &Numerics) -> Numerics {
+        let cast = strongest_cast(self, divisor);
+        match cast {
+            StaticTypes::TReal => {
+                Numerics::Real((self.approximate() / divisor.approximate()).trunc())
+            },
+            StaticTypes::TRational => {
+                let (a, b) = (self.fraction().unwrap(), divisor.fraction().unwrap());
+                Numerics::Rational((a / b).trunc())
+            },
+            _ => {
+                let (a, b) = (self.exact().unwrap(), divisor.exact().unwrap());
+                integral(a / b, cast)
+            }
+        }
+    }
+
+    /// Remainder of flooring division, which takes the sign of the divisor.
+    pub fn modulo(&self, divisor : &Numerics) -> Numerics {
+        let cast = strongest_cast(self, divisor);
+        match cast {
+            StaticTypes::TReal => {
+                let (a, b) = (self.approximate(), divisor.approximate());
+                Numerics::Real(a - b * (a / b).floor())
+            },
+            StaticTypes::TRational => {
+                let (a, b) = (self.fraction().unwrap(), divisor.fraction().unwrap());
+                Numerics::Rational(&a - &b * (&a / &b).floor())
+            },
+            _ => {
+                let (a, b) = (self.exact().unwrap(), divisor.exact().unwrap());
+                let mut remainder = a % &b;
+                if remainder.sign() != Sign::NoSign && remainder.sign() != b.sign() {
+                    remainder += b;
+                }
+                integral(remainder, cast)
+            }
+        }
+    }
+
+    /// Compare numbers by value, regardless of their types.
+    pub fn compare(&self, other : &Numerics) -> Option<Ordering> {
+        match (self.fraction(), other.fraction()) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => self.approximate().partial_cmp(&other.approximate())
+        }
+    }
 }
 
-macro_rules! new_base {
-    ($arg:expr, $base:ident) => {
-        match &$arg {
-            Numerics::Natural(n) => *n as $base,
-            Numerics::Integer(n) => *n as $base,
-            Numerics::Real(n)    => *n as $base,
-        };
-    };
+/// Largest power (in bits) worth computing at compile time.
+const MAX_POWER_BITS : u64 = 1 << 16;
+
+/// Demote an exact result of operating on numbers of type `cast'.
+/// Naturals stay natural, unless they went negative.
+fn integral(result : BigInt, cast : StaticTypes) -> Numerics {
+    if cast == StaticTypes::TNatural && result.sign() != Sign::Minus {
+        return result.magnitude().to_numeric();
+    }
+    result.to_numeric()
+}
+
+/// The static type of a number.
+fn numeric_type(number : &Numerics) -> StaticTypes {
+    match number {
+        Numerics::Natural(_)
+        | Numerics::BigNatural(_) => StaticTypes::TNatural,
+        Numerics::Integer(_)
+        | Numerics::BigInteger(_) => StaticTypes::TInteger,
+        Numerics::Rational(_) => StaticTypes::TRational,
+        Numerics::Real(_) => StaticTypes::TReal,
+    }
+}
+
+/// How strong a numeric type is, a weaker type
+/// may always be cast up to a stronger one.
+pub fn cast_strength(st : &StaticTypes) -> i32 {
+    match st {
+        StaticTypes::TReal     => 4,
+        StaticTypes::TRational => 3,
+        StaticTypes::TInteger  => 2,
+        StaticTypes::TNatural  => 0,
+        _ => -1,
+    }
 }
 
+fn strongest_cast(left : &Numerics, right : &Numerics) -> StaticTypes {
+    let (left, right) = (numeric_type(left), numeric_type(right));
+    if cast_strength(&left) > cast_strength(&right) { left } else { right }
+}
+
+/// Naturals and integers are operated on exactly, and the result
+/// is demoted back to a pointer-sized number if it fits.
+/// Rationals are also exact, any operation involving a real
+/// is done on doubles.
 macro_rules! fold_on_numeric {
     ($op:tt, $left:expr, $right:expr) => {
         {
-            let cast = strongest_cast($left, $right);
+            let cast = strongest_cast(&$left, &$right);
             match cast {
-                StaticTypes::TNatural => (new_base!($left, usize) $op new_base!($right, usize)).to_numeric(),
-                StaticTypes::TInteger => (new_base!($left, isize) $op new_base!($right, isize)).to_numeric(),
-                StaticTypes::TReal    => (new_base!($left,   f64) $op new_base!($right,   f64)).to_numeric(),
-                _ => panic!("Numeric porting non-numeric type?")
+                StaticTypes::TReal => ($left.approximate() $op $right.approximate()).to_numeric(),
+                StaticTypes::TRational => Numerics::Rational(
+                    $left.fraction().unwrap() $op $right.fraction().unwrap()),
+                _ => integral($left.exact().unwrap() $op $right.exact().unwrap(), cast)
             }
         }
     };
@@ -79,15 +262,21 @@ impl ops::Add<Numerics> for Numerics {
 impl ops::Sub<Numerics> for Numerics {
     type Output = Numerics;
     fn sub(self, right : Numerics) -> Numerics {
-        if fold_on_numeric!(>, right, self) == Numerics::Natural(1) {
-            if let Numerics::Natural(u) = right {
-                return fold_on_numeric!(-, self, Numerics::Integer(u as isize));
-            }
-        }
         fold_on_numeric!(-, self, right)
     }
 }
 
+impl ops::Neg for Numerics {
+    type Output = Numerics;
+    fn neg(self) -> Numerics {
+        match self {
+            Numerics::Rational(q) => Numerics::Rational(-q),
+            Numerics::Real(r) => Numerics::Real(-r),
+            _ => (-self.exact().unwrap()).to_numeric()
+        }
+    }
+}
+
 impl ops::Mul<Numerics> for Numerics {
     type Output = Numerics;
     fn mul(self, right : Numerics) -> Numerics {
@@ -98,24 +287,39 @@ impl ops::Mul<Numerics> for Numerics {
 impl ops::Div<Numerics> for Numerics {
     type Output = Numerics;
     fn div(self, right : Numerics) -> Numerics {
+        // Dividing naturals or integers gives an exact fraction.
+        let cast = strongest_cast(&self, &right);
+        if cast_strength(&cast) < cast_strength(&StaticTypes::TRational) {
+            return Numerics::Rational(self.fraction().unwrap() / right.fraction().unwrap());
+        }
         fold_on_numeric!(/, self, right)
     }
 }
 
-/// Parse a string of more than two chars with a specified radix, into an ast::Numeric.
-fn parse_with_radix(neg : bool, s : &str, radix : u32) -> Numerics {
-    let unsigned = usize::from_str_radix(s.get(2..).unwrap(), radix).unwrap();
+/// Negate a parsed unsigned literal, if it was written with a minus.
+fn signed(neg : bool, unsigned : BigUint) -> Numerics {
     if neg {
-        return Numerics::Integer(-(unsigned as isize));
+        return (-BigInt::from(unsigned)).to_numeric();
     }
-    return Numerics::Natural(unsigned);
+    return unsigned.to_numeric();
+}
+
+/// Parse a string of more than two chars with a specified radix, into an ast::Numeric.
+fn parse_with_radix(neg : bool, s : &str, radix : u32) -> Numerics {
+    let unsigned = BigUint::from_str_radix(s.get(2..).unwrap(), radix).unwrap();
+    signed(neg, unsigned)
 }
 
 /// Converts primitive types into ast::Numerics.
 pub trait ToNumeric { fn to_numeric(&self) -> Numerics; }
+/// Parses a numeric literal, as matched by the lexer.  Digits may be
+/// separated by underscores (`1_000'), and hexadecimal digits may be
+/// in either case.  A decimal literal is real if it has a fractional
+/// part or a negative exponent (`2.5', `25e-1'), otherwise it is exact,
+/// i.e. `25e3' is the natural `25000'.
 impl ToNumeric for &str {
     fn to_numeric(&self) -> Numerics {
-        let mut test_str = <&str>::clone(self).to_ascii_lowercase();
+        let mut test_str = self.to_ascii_lowercase().replace('_', "");
 
         let is_neg = self.starts_with('-');
         if is_neg { test_str = test_str.get(1..).unwrap().to_string(); }
@@ -126,22 +330,18 @@ impl ToNumeric for &str {
             Some("0b") => parse_with_radix(is_neg, &test_str,  2),
             Some(_) => {
                 let exp_notation : Vec<&str> = test_str.split('e').collect();
-                let     mantissa : &str = exp_notation.get(0).unwrap();
-                let mut exponent : &str = exp_notation.get(1).unwrap_or(&"0");
-                if exponent.is_empty() { exponent = "0"; }
-                let exponent : i32 = exponent.parse().unwrap();
+                let mantissa : &str = exp_notation.first().unwrap();
+                let exponent : &str = exp_notation.get(1).unwrap_or(&"0");
 
-                if mantissa.contains('.') || exponent < 0 {
-                    let mut number = mantissa.parse::<f64>().unwrap() * 10f64.powi(exponent);
+                if mantissa.contains('.') || exponent.starts_with('-') {
+                    let mut number = test_str.parse::<f64>().unwrap();
                     if is_neg { number *= -1f64; }
                     return Numerics::Real(number);
                 }
 
-                let number : usize = mantissa.parse().unwrap();
-                if is_neg {
-                    return Numerics::Integer(-(number as isize) * 10isize.pow(exponent as u32));
-                }
-                return Numerics::Natural(number * 10usize.pow(exponent as u32));
+                let exponent : u32 = exponent.trim_start_matches('+').parse().unwrap();
+                let number : BigUint = mantissa.parse().unwrap();
+                signed(is_neg, number * BigUint::from(10u8).pow(exponent))
             }
             None => {
                 if is_neg {
@@ -175,6 +375,23 @@ impl ToNumeric for i32 {
         Numerics::Integer(*self as isize)
     }
 }
+impl ToNumeric for BigUint {
+    fn to_numeric(&self) -> Numerics {
+        match usize::try_from(self) {
+            Ok(n)  => Numerics::Natural(n),
+            Err(_) => Numerics::BigNatural(self.clone())
+        }
+    }
+}
+impl ToNumeric for BigInt {
+    fn to_numeric(&self) -> Numerics {
+        if self.sign() == Sign::Plus { return self.magnitude().to_numeric(); }
+        match isize::try_from(self) {
+            Ok(n)  => Numerics::Integer(n),
+            Err(_) => Numerics::BigInteger(self.clone())
+        }
+    }
+}
 impl ToNumeric for f64 {
     fn to_numeric(&self) -> Numerics { Numerics::Real(*self) }
 }
@@ -182,9 +399,12 @@ impl ToNumeric for f64 {
 impl fmt::Display for Numerics {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let printable = match self {
-            Numerics::Natural(n) => n.to_string(),
-            Numerics::Integer(n) => n.to_string(),
-            Numerics::Real(n)    => n.to_string(),
+            Numerics::Natural(n)    => n.to_string(),
+            Numerics::Integer(n)    => n.to_string(),
+            Numerics::BigNatural(n) => n.to_string(),
+            Numerics::BigInteger(n) => n.to_string(),
+            Numerics::Rational(q)   => q.to_string(),
+            Numerics::Real(n)       => n.to_string(),
         };
         write!(f, "{}", printable)
     }
@@ -211,11 +431,82 @@ pub struct StrNode {
     pub site : Site,
 }
 
+/// Interpolated string, e.g. `"Hello, :{name}!"',
+/// made up of string literals and embedded expressions.
+#[derive(Clone)]
+pub struct InterpNode {
+    /// Pieces of the string, concatenated in order.
+    pub segments : Vec<Nodes>,
+
+    /// Source location.
+    pub site : Site,
+}
+
+/// Enumerated set literal, e.g. `[ 1; 2; 3 ]'.
+#[derive(Clone)]
+pub struct SetNode {
+    /// Elements of the set, in the order they were written.
+    pub elements : Vec<Nodes>,
+    /// Type common to every element, found by the type resolver.
+    pub element_type : StaticTypes,
+
+    /// Source location.
+    pub site : Site,
+}
+
+/// Vector literal, e.g. `[| 1; 2; 3 |]'.
+#[derive(Clone)]
+pub struct VecNode {
+    /// Elements of the vector, in order.
+    pub elements : Vec<Nodes>,
+    /// Type common to every element, found by the type resolver.
+    pub element_type : StaticTypes,
+
+    /// Source location.
+    pub site : Site,
+}
+
+/// Tuple, e.g. `(3, 9, "John", -2.6)'.
+#[derive(Clone)]
+pub struct TupleNode {
+    /// Elements of the tuple, in order.
+    pub elements : Vec<Nodes>,
+
+    /// Source location.
+    pub site : Site,
+}
+
+/// Variable of a set-builder, and the set it ranges over,
+/// e.g. `n : Nat' or `n <- Nat'.
+#[derive(Clone)]
+pub struct Generator {
+    pub variable : IdentNode,
+    pub domain : Nodes,
+}
+
+/// Set-builder notation, e.g. `[ n : Nat => n mod 3 == 0 ]'
+/// or `[ 3*n => n <- Nat ]'.
+#[derive(Clone)]
+pub struct BuilderNode {
+    /// Expression giving each element, from the variables.
+    pub element : Box<Nodes>,
+    /// Variables bound by the set-builder, in the order they were written.
+    pub generators : Vec<Generator>,
+    /// Predicate every element must satisfy, if any.
+    pub condition : Option<Box<Nodes>>,
+    /// Type of the elements, found by the type resolver.
+    pub element_type : StaticTypes,
+
+    /// Source location.
+    pub site : Site,
+}
+
 /// Symbol Node.
 #[derive(Clone)]
 pub struct SymNode {
     /// Value/name stored as a string and
-    /// excludes the colon (:) in front.
+    /// excludes the colon (:) in front.  Quoted symbols,
+    /// e.g. `:"hello world"', exclude their quotes too.
     pub value : String,
 
     /// Source location.
@@ -234,6 +525,9 @@ pub struct CallNode {
     /// What type it returns.
     pub return_type : StaticTypes,
 
+    /// Whether the call was written in parentheses, e.g. `(A * B)'.
+    pub parenthesised : bool,
+
     /// Source location.
     pub site : Site,
 }
@@ -242,6 +536,8 @@ pub struct CallNode {
 /// in order of when they will be executed.
 #[derive(Clone)]
 pub struct BlockNode {
+    /// Keyword opening the block, e.g. `do' or `where'.
+    pub keyword : String,
     /// Pointer to list of nodes in the code block.
     pub statements : Vec<Nodes>,
 
@@ -270,9 +566,16 @@ pub struct NilNode {
 pub enum StaticTypes {
     TNatural, TInteger, TReal,
     TString, TSymbol,
+    /// Exact fractions, referenced as 8 in bytecode.
+    TRational,
     TSet(Box<StaticTypes>),
     /// TFunction(boxed operand type, boxed return type)
     TFunction(Box<StaticTypes>, Box<StaticTypes>),
+    /// TVector(boxed element type, length), e.g. `Nat^3'.
+    TVector(Box<StaticTypes>, usize),
+    /// Cartesian product of differing types, e.g. `Int * String'.
+    /// Products of a single type are vectors, see `StaticTypes::product'.
+    TTuple(Vec<StaticTypes>),
 
     TNil,
     TUnknown
@@ -286,10 +589,21 @@ impl StaticTypes {
         None
     }
 
+    /// Type of tuples of the given factors.  Tuples with elements
+    /// all of the same type are vectors, i.e. `Real * Real' is `Real^2'.
+    pub fn product(factors : Vec<StaticTypes>) -> StaticTypes {
+        match factors.first() {
+            Some(first) if factors.iter().all(|factor| factor == first) =>
+                StaticTypes::TVector(Box::new(first.to_owned()), factors.len()),
+            _ => StaticTypes::TTuple(factors)
+        }
+    }
+
     pub fn is_number(&self) -> bool {
         match self {
             StaticTypes::TNatural
             | StaticTypes::TInteger
+            | StaticTypes::TRational
             | StaticTypes::TReal => true,
             _ => false
         }
@@ -303,12 +617,14 @@ impl fmt::Display for StaticTypes {
             StaticTypes::TNatural => "natural",
             StaticTypes::TInteger => "integer",
             StaticTypes::TReal    => "real",
+            StaticTypes::TRational => "rational",
             StaticTypes::TString  => "string",
             StaticTypes::TSymbol  => "symbol",
             StaticTypes::TSet(st) => match *st.clone() {
                 StaticTypes::TNatural => "Nat",
                 StaticTypes::TInteger => "Int",
                 StaticTypes::TReal    => "Real",
+                StaticTypes::TRational => "Rat",
                 StaticTypes::TString  => "String",
                 StaticTypes::TSymbol  => "Sym",
                 StaticTypes::TFunction(o, r) => {
@@ -317,6 +633,16 @@ impl fmt::Display for StaticTypes {
                 },
                 StaticTypes::TNil     => "Empty",
                 StaticTypes::TUnknown => "Any",
+                StaticTypes::TVector(et, n) => {
+                    ss = format!("{}^{}", StaticTypes::TSet(et), n);
+                    ss.as_str()
+                },
+                StaticTypes::TTuple(factors) => {
+                    ss = factors.into_iter()
+                        .map(|factor| StaticTypes::TSet(Box::new(factor)).to_string())
+                        .collect::<Vec<String>>().join(" * ");
+                    ss.as_str()
+                },
                 _ => {
                     ss = format!("Set {}", st);
                     ss.as_str()
@@ -326,6 +652,16 @@ impl fmt::Display for StaticTypes {
                 ss = format!("({} \u{21a6} {})", o, r);
                 ss.as_str()
             },
+            StaticTypes::TVector(et, n) => {
+                ss = format!("{}^{}", et, n);
+                ss.as_str()
+            },
+            StaticTypes::TTuple(factors) => {
+                ss = format!("({})", factors.iter()
+                    .map(StaticTypes::to_string)
+                    .collect::<Vec<String>>().join(", "));
+                ss.as_str()
+            },
             StaticTypes::TNil     => "nothing",
             StaticTypes::TUnknown => "unknown",
         };
@@ -339,7 +675,12 @@ pub enum Nodes {
     Ident(IdentNode),
     Num(NumNode),
     Str(StrNode),
+    Interp(InterpNode),
     Sym(SymNode),
+    Set(SetNode),
+    Builder(BuilderNode),
+    Vec(VecNode),
+    Tuple(TupleNode),
     Call(CallNode),
     Block(BlockNode),
     File(FileNode),
@@ -354,11 +695,27 @@ impl fmt::Display for Nodes {
             Nodes::Ident(node)  => format!("%ident{{ :value \"{}\"; :yield {} }}", node.value, yt),
             Nodes::Num(node)    => format!("%num{{ :value {}; :yield {} }}", node.value, yt),
             Nodes::Str(node)    => format!("%str{{ :value \"{}\"; :yield {} }}", node.value, yt),
+            Nodes::Interp(node) => format!("%interp{{ :segments [| {} |] }}",
+                node.segments.iter().map(Nodes::to_string).collect::<Vec<String>>().join("; ")),
             Nodes::Sym(node)    => format!("%sym{{ :value \":{}\"; :yield {} }}", node.value, yt),
+            Nodes::Set(node)    => format!("%set{{ :elements [| {} |]; :yield {} }}",
+                node.elements.iter().map(Nodes::to_string).collect::<Vec<String>>().join("; "), yt),
+            Nodes::Vec(node)    => format!("%vec{{ :elements [| {} |]; :yield {} }}",
+                node.elements.iter().map(Nodes::to_string).collect::<Vec<String>>().join("; "), yt),
+            Nodes::Tuple(node)  => format!("%tuple{{ :elements [| {} |]; :yield {} }}",
+                node.elements.iter().map(Nodes::to_string).collect::<Vec<String>>().join("; "), yt),
+            Nodes::Builder(node) => format!("%builder{{ :element {}; :generators [| {} |]; :condition {}; :yield {} }}",
+                node.element,
+                node.generators.iter()
+                    .map(|generator| format!("{} <- {}", generator.variable.value, generator.domain))
+                    .collect::<Vec<String>>().join("; "),
+                node.condition.as_ref().map_or(String::from("()"), |condition| condition.to_string()),
+                yt),
             Nodes::Call(node)   => format!(
                 "%call{{\n  :yield {}\n  :callee ({})\n  :operands [|\n    {}\n  |]\n}}", yt, node.callee,
                 node.operands.iter().map(Nodes::to_string).collect::<Vec<String>>().join("\n    ")),
-            Nodes::Block(node)  => format!("%block{{ {} }}",
+            Nodes::Block(node)  => format!("%block{{ :keyword {}; {} }}",
+                node.keyword,
                 node.statements
                 .iter()
                 .map(Nodes::to_string)
@@ -388,7 +745,12 @@ impl Nodes {
             Nodes::Call(n)  => n.site.to_owned(),
             Nodes::Num(n)   => n.site.to_owned(),
             Nodes::Str(n)   => n.site.to_owned(),
+            Nodes::Interp(n) => n.site.to_owned(),
             Nodes::Sym(n)   => n.site.to_owned(),
+            Nodes::Set(n)   => n.site.to_owned(),
+            Nodes::Builder(n) => n.site.to_owned(),
+            Nodes::Vec(n)   => n.site.to_owned(),
+            Nodes::Tuple(n) => n.site.to_owned(),
             Nodes::Nil(n)   => n.site.to_owned(),
             Nodes::Block(n) => n.site.to_owned(),
             Nodes::File(n)  => n.site.to_owned(),
@@ -401,22 +763,34 @@ impl Nodes {
 
     /// Function that returns the statically known type
     /// of any syntactic node generated.
+    /// Identifiers in `BUILTIN_SETS` yield the sets they name.
     pub fn yield_type(&self) -> StaticTypes {
         match self {
             Nodes::Num(num) => {
                 match num.value {
-                    Numerics::Natural(_) => StaticTypes::TNatural,
-                    Numerics::Integer(_) => StaticTypes::TInteger,
-                    Numerics::Real(_)    => StaticTypes::TReal,
+                    Numerics::Natural(_)
+                    | Numerics::BigNatural(_) => StaticTypes::TNatural,
+                    Numerics::Integer(_)
+                    | Numerics::BigInteger(_) => StaticTypes::TInteger,
+                    Numerics::Rational(_) => StaticTypes::TRational,
+                    Numerics::Real(_) => StaticTypes::TReal,
                 }
             },
-            Nodes::Str(_) => StaticTypes::TString,
+            Nodes::Str(_)
+            | Nodes::Interp(_) => StaticTypes::TString,
             Nodes::Sym(_) => StaticTypes::TSymbol,
+            Nodes::Set(set) => StaticTypes::TSet(Box::new(set.element_type.to_owned())),
+            Nodes::Builder(builder) => StaticTypes::TSet(Box::new(builder.element_type.to_owned())),
+            Nodes::Vec(vec) => StaticTypes::TVector(
+                Box::new(vec.element_type.to_owned()), vec.elements.len()),
+            Nodes::Tuple(tuple) => StaticTypes::product(
+                tuple.elements.iter().map(Nodes::yield_type).collect()),
             Nodes::Ident(ident) => {
                 match ident.value.as_str() {
                     "Nat"  => StaticTypes::TSet(Box::new(StaticTypes::TNatural)),
                     "Int"  => StaticTypes::TSet(Box::new(StaticTypes::TInteger)),
                     "Real" => StaticTypes::TSet(Box::new(StaticTypes::TReal)),
+                    "Rat" | "Rational" => StaticTypes::TSet(Box::new(StaticTypes::TRational)),
                     "Str" | "String" => StaticTypes::TSet(Box::new(StaticTypes::TString)),
                     "Sym" | "Symbol" => StaticTypes::TSet(Box::new(StaticTypes::TSymbol)),
                     "Empty" => StaticTypes::TSet(Box::new(StaticTypes::TNil)),
@@ -441,6 +815,25 @@ impl Nodes {
                                             Box::new(sub_call.operands[0].yield_type()),
                                             Box::new(call.operands[0].yield_type()))));
                                 },
+                                // Vectors of `n' elements of a set, e.g. `Nat^3'.
+                                "^" => {
+                                    let element = sub_call.operands[0].yield_type().set_inner();
+                                    let length = call.operands[0].num().map(|num| &num.value);
+                                    if let (Some(element), Some(Numerics::Natural(n))) = (element, length) {
+                                        return StaticTypes::TSet(
+                                            Box::new(StaticTypes::TVector(Box::new(element), *n)));
+                                    }
+                                },
+                                // Cartesian products of sets, e.g. `Int * String * Real'.
+                                "*" => {
+                                    let factors : Option<Vec<StaticTypes>> = self.product_factors().iter()
+                                        .map(|factor| factor.yield_type().set_inner())
+                                        .collect();
+                                    if let Some(factors) = factors {
+                                        return StaticTypes::TSet(
+                                            Box::new(StaticTypes::product(factors)));
+                                    }
+                                },
                                 _ => ()
                             }
                         }
@@ -449,8 +842,12 @@ impl Nodes {
                 };
                 call.return_type.to_owned()
             },
-            Nodes::Block(_)
-            | Nodes::File(_) => StaticTypes::TUnknown,
+            // A block gives the value of its last statement.
+            Nodes::Block(block) => match block.statements.last() {
+                Some(last) if !last.is_definition() => last.yield_type(),
+                _ => StaticTypes::TNil
+            },
+            Nodes::File(_) => StaticTypes::TUnknown,
             Nodes::Nil(_)    => StaticTypes::TNil,
         }
     }
@@ -468,7 +865,12 @@ impl Nodes {
             Nodes::Ident(_) => "identifier",
             Nodes::Num(_)   => "numeric",
             Nodes::Str(_)   => "string literal",
+            Nodes::Interp(_) => "interpolated string",
             Nodes::Sym(_)   => "symbol",
+            Nodes::Set(_)   => "set literal",
+            Nodes::Builder(_) => "set-builder",
+            Nodes::Vec(_)   => "vector literal",
+            Nodes::Tuple(_) => "tuple",
             Nodes::Nil(_)   => "nothing",
             Nodes::Call(_)  => "application",
             Nodes::Block(_) => "code block",
@@ -485,10 +887,35 @@ impl Nodes {
         }
     }
 
+    /// Operands of a chain of `*', e.g. `A', `B' and `C' of `A * B * C'.
+    /// Products in parentheses are factors themselves, as in `(A * B) * C'.
+    pub fn product_factors(&self) -> Vec<&Nodes> {
+        if let Nodes::Call(call) = self {
+            if call.is_binary() {
+                let partial = call.callee.call().unwrap();
+                if partial.callee.ident().is_some_and(|op| op.value == "*") {
+                    let left = &partial.operands[0];
+                    let mut factors = match left {
+                        Nodes::Call(product) if product.parenthesised => vec![left],
+                        _ => left.product_factors()
+                    };
+                    factors.push(&call.operands[0]);
+                    return factors;
+                }
+            }
+        }
+        vec![self]
+    }
+
     pub fn ident(&self) -> Option<&IdentNode> { unwrap_enum!(self, Nodes::Ident) }
     pub fn   num(&self) -> Option<&NumNode>   { unwrap_enum!(self, Nodes::Num)   }
     pub fn   str(&self) -> Option<&StrNode>   { unwrap_enum!(self, Nodes::Str)   }
+    pub fn interp(&self) -> Option<&InterpNode> { unwrap_enum!(self, Nodes::Interp) }
     pub fn   sym(&self) -> Option<&SymNode>   { unwrap_enum!(self, Nodes::Sym)   }
+    pub fn   set(&self) -> Option<&SetNode>   { unwrap_enum!(self, Nodes::Set)   }
+    pub fn builder(&self) -> Option<&BuilderNode> { unwrap_enum!(self, Nodes::Builder) }
+    pub fn   vec(&self) -> Option<&VecNode>   { unwrap_enum!(self, Nodes::Vec)   }
+    pub fn tuple(&self) -> Option<&TupleNode> { unwrap_enum!(self, Nodes::Tuple) }
     pub fn  call(&self) -> Option<&CallNode>  { unwrap_enum!(self, Nodes::Call)  }
     pub fn block(&self) -> Option<&BlockNode> { unwrap_enum!(self, Nodes::Block) }
     pub fn  file(&self) -> Option<&FileNode>  { unwrap_enum!(self, Nodes::File)  }
@@ -498,6 +925,10 @@ impl Nodes {
     pub fn is_num(&self)   -> bool { self.num().is_some()   }
     pub fn is_str(&self)   -> bool { self.str().is_some()   }
     pub fn is_sym(&self)   -> bool { self.sym().is_some()   }
+    pub fn is_set(&self)   -> bool { self.set().is_some()   }
+    pub fn is_builder(&self) -> bool { self.builder().is_some() }
+    pub fn is_vec(&self)   -> bool { self.vec().is_some()   }
+    pub fn is_tuple(&self) -> bool { self.tuple().is_some() }
     pub fn is_call(&self)  -> bool { self.call().is_some()  }
     pub fn is_block(&self) -> bool { self.block().is_some() }
     pub fn is_file(&self)  -> bool { self.file().is_some()  }
@@ -522,6 +953,15 @@ impl Nodes {
             _ => false
         }
     }
+
+    /// Checks if the node is an assignment or a type annotation,
+    /// e.g. `a = 1' or `a : Nat', which are statements without a value.
+    pub fn is_definition(&self) -> bool {
+        self.call()
+            .filter(|call| call.is_binary())
+            .and_then(|call| call.callee.call()?.callee.ident())
+            .is_some_and(|ident| ident.value == "=" || ident.value == ":")
+    }
 }
 
 impl IdentNode {
@@ -532,6 +972,17 @@ impl IdentNode {
             site
         })
     }
+
+    /// Checks if the identifier is the wildcard `_', matching anything.
+    pub fn is_wildcard(&self) -> bool {
+        self.value == "_"
+    }
+
+    /// Constructors (and constants) start with a capital, e.g. `Zero', and
+    /// in patterns they are matched against, rather than bound to.
+    pub fn is_constructor(&self) -> bool {
+        self.value.starts_with(char::is_uppercase)
+    }
 }
 
 impl NumNode {
@@ -557,6 +1008,7 @@ impl CallNode {
             callee: Box::new(callee),
             operands,
             return_type: StaticTypes::TUnknown,
+            parenthesised: false,
             site
         })
     }
@@ -586,7 +1038,7 @@ impl CallNode {
 
             if let Nodes::Call(call) = call_node {
                 pushable.push_front(call.operands[0].clone());
-                return make_argument_vector(&*call.callee, pushable);
+                return make_argument_vector(&call.callee, pushable);
             }
 
             pushable.push_front(call_node.clone());
@@ -628,6 +1080,62 @@ impl CallNode {
     }
 }
 
+impl InterpNode {
+    pub fn new(segments : Vec<Nodes>, site : Site) -> Nodes
+        { Nodes::Interp(InterpNode { segments, site }) }
+}
+
+impl SetNode {
+    /// Elements are of an unknown type, until they are resolved.
+    pub fn new(elements : Vec<Nodes>, site : Site) -> Nodes {
+        Nodes::Set(SetNode {
+            elements,
+            element_type: StaticTypes::TUnknown,
+            site
+        })
+    }
+}
+
+impl TupleNode {
+    pub fn new(elements : Vec<Nodes>, site : Site) -> Nodes
+        { Nodes::Tuple(TupleNode { elements, site }) }
+}
+
+impl VecNode {
+    /// Elements are of an unknown type, until they are resolved.
+    pub fn new(elements : Vec<Nodes>, site : Site) -> Nodes {
+        Nodes::Vec(VecNode {
+            elements,
+            element_type: StaticTypes::TUnknown,
+            site
+        })
+    }
+}
+
+impl BuilderNode {
+    /// Elements are of an unknown type, until they are resolved.
+    pub fn new(element : Nodes, generators : Vec<Generator>,
+               condition : Option<Nodes>, site : Site) -> Nodes {
+        Nodes::Builder(BuilderNode {
+            element: Box::new(element),
+            generators,
+            condition: condition.map(Box::new),
+            element_type: StaticTypes::TUnknown,
+            site
+        })
+    }
+}
+
+impl BlockNode {
+    pub fn new(keyword : &str, statements : Vec<Nodes>, site : Site) -> Nodes {
+        Nodes::Block(BlockNode {
+            keyword: keyword.to_owned(),
+            statements,
+            site
+        })
+    }
+}
+
 impl FileNode {
     pub fn new(filename : String, site : Site) -> Nodes
         { Nodes::File(FileNode { filename, site }) }
@@ -640,15 +1148,30 @@ impl NilNode {
 /// Root branch of the AST.
 pub struct Root {
     pub branches : Vec<Nodes>,
-    pub filename : String
+    pub filename : String,
+    /// Warnings produced whilst parsing and analysing the tree.
+    pub issues : Diagnostics,
+    /// Levels of warnings given by pragmas in the file.
+    pub lints : LintLevels,
 }
 
 impl Root {
     pub fn new(filename : &str) -> Self {
-        Root { branches: Vec::new(), filename: filename.to_owned() }
+        Root {
+            branches: Vec::new(),
+            filename: filename.to_owned(),
+            issues: Diagnostics::new(),
+            lints: LintLevels::new()
+        }
     }
 }
 
+/// Names of the built-in sets.
+pub const BUILTIN_SETS : &[&str] = &[
+    "Nat", "Int", "Real", "Rat", "Rational", "Str", "String",
+    "Sym", "Symbol", "Empty", "Any", "Anything"
+];
+
 const TAB : &str = "  ";
 
 pub fn pretty_print(node : &Nodes, depth : usize) -> String {
@@ -658,7 +1181,7 @@ pub fn pretty_print(node : &Nodes, depth : usize) -> String {
             "{tab}%call{{\n{tab}{T}:yield {yt}\n{tab}{T}:callee (\n{calling}\n{tab}{T})\n{tab}{T}:operand [|{op}|]\n{tab}}}",
             tab=tab, T=TAB,
             yt=n.return_type,
-            calling=pretty_print(&*n.callee, depth + 2),
+            calling=pretty_print(&n.callee, depth + 2),
             op=(if n.operands.is_empty() { String::from(" ") } else { format!(
                 "\n{ops}\n{tab}{T}",
                 ops=pretty_print(&n.operands[0], depth + 2),
@@ -0,0 +1,307 @@
+use std::fmt;
+use std::collections::VecDeque;
+
+use super::{ast, lexer, parser};
+use super::token::{Token, TokenType};
+
+use crate::issue::Diagnostics;
+
+/// A single token, along with the exact source it was lexed from.
+#[derive(Clone)]
+pub struct Leaf {
+    pub token : Token,
+    /// Source text of the token, empty for virtual tokens.
+    pub text : String,
+}
+
+/// Kinds of branches in the concrete syntax tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    /// Every statement (and trivia between them) in the file.
+    Root,
+    /// Tokens of a statement, up to and including its terminator.
+    Statement,
+    /// Parenthesised expression, e.g. `(1 + 2)' or `(+ 1)'.
+    Paren,
+    /// Square brackets, `[...]'.
+    Brack,
+    /// Curly braces, `{...}'.
+    Brace,
+    /// Vector brackets.
+    Vector,
+    /// Indentation block, from its opener (e.g. `do:') to its virtual end.
+    Block,
+    /// Interpolated string, with the expressions embedded in it.
+    Interpolation,
+}
+
+/// Branch of the tree, with its children in source order.
+#[derive(Clone)]
+pub struct Node {
+    pub kind : NodeKind,
+    pub children : Vec<Element>,
+}
+
+/// Either a token, or a branch of tokens.
+#[derive(Clone)]
+pub enum Element {
+    Leaf(Leaf),
+    Node(Node),
+}
+
+/// Lossless concrete syntax tree of a file.  Unlike the AST, it keeps
+/// whitespace, comments, brackets and pragmas, so that the source can be
+/// reproduced exactly, byte-for-byte, by displaying the tree.
+pub struct Tree {
+    pub root : Node,
+    pub filename : String,
+}
+
+impl Element {
+    /// Checks if the element is whitespace or a comment.
+    pub fn is_trivia(&self) -> bool {
+        match self {
+            Element::Leaf(leaf) => leaf.token.is_trivia(),
+            Element::Node(_) => false
+        }
+    }
+
+    fn class(&self) -> Option<TokenType> {
+        match self {
+            Element::Leaf(leaf) => Some(leaf.token.class),
+            Element::Node(_) => None
+        }
+    }
+}
+
+impl Node {
+    /// Every leaf under the node, in source order.
+    pub fn leaves(&self) -> Vec<&Leaf> {
+        let mut leaves = vec![];
+        for child in &self.children {
+            match child {
+                Element::Leaf(leaf) => leaves.push(leaf),
+                Element::Node(node) => leaves.extend(node.leaves())
+            }
+        }
+        leaves
+    }
+}
+
+impl Tree {
+    /// Tokens of the tree the parser needs, i.e. all but the trivia.
+    pub fn tokens(&self) -> VecDeque<Token> {
+        self.root.leaves().into_iter()
+            .filter(|leaf| !leaf.token.is_trivia())
+            .map(|leaf| leaf.token.clone())
+            .collect()
+    }
+
+    /// Parses the tree into an AST, as `syntax::parse_source' would,
+    /// but without analysing it.  Pragmas are not part of the AST.
+    pub fn to_ast(&self, issues : &mut Diagnostics) -> ast::Root {
+        let stream = self.tokens().into_iter()
+            .filter(|token| token.class != TokenType::Pragma)
+            .collect();
+        parser::parse(stream, &self.filename, issues)
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for leaf in self.leaves() {
+            write!(f, "{}", leaf.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.root)
+    }
+}
+
+/// Branch opened by a token, and the token that closes it.
+fn opens(class : TokenType) -> Option<(NodeKind, TokenType)> {
+    Some(match class {
+        TokenType::LParen  => (NodeKind::Paren, TokenType::RParen),
+        TokenType::LBrack  => (NodeKind::Brack, TokenType::RBrack),
+        TokenType::LBrace  => (NodeKind::Brace, TokenType::RBrace),
+        TokenType::LVec    => (NodeKind::Vector, TokenType::RVec),
+        TokenType::LBlock  => (NodeKind::Block, TokenType::RBlock),
+        TokenType::StrHead => (NodeKind::Interpolation, TokenType::StrTail),
+        _ => return None
+    })
+}
+
+struct Builder {
+    leaves : VecDeque<Leaf>,
+    /// Closers of the branches currently open.
+    closers : Vec<TokenType>,
+}
+
+impl Builder {
+    /// Children of a branch, up to (but not including) its closer, or the
+    /// closer of a branch enclosing it, if it is never closed.
+    fn children(&mut self) -> Vec<Element> {
+        let mut children = vec![];
+        while let Some(leaf) = self.leaves.front() {
+            let class = leaf.token.class;
+            if class == TokenType::EOF || self.closers.contains(&class) {
+                break;
+            }
+            // The middle of an interpolated string closes the expression
+            //  before it, and opens the next one.
+            if class == TokenType::StrMid {
+                if self.closers.last() != Some(&TokenType::StrTail) { break; }
+                children.push(Element::Leaf(self.leaves.pop_front().unwrap()));
+                continue;
+            }
+            let leaf = self.leaves.pop_front().unwrap();
+            match opens(class) {
+                Some((kind, closer)) => children.push(Element::Node(self.branch(leaf, kind, closer))),
+                None => children.push(Element::Leaf(leaf))
+            }
+        }
+        children
+    }
+
+    fn branch(&mut self, opener : Leaf, kind : NodeKind, closer : TokenType) -> Node {
+        self.closers.push(closer);
+        let mut children = vec![Element::Leaf(opener)];
+        let inner = self.children();
+        if kind == NodeKind::Block {
+            children.extend(statements(inner));
+        } else {
+            children.extend(inner);
+        }
+        self.closers.pop();
+
+        if self.leaves.front().is_some_and(|leaf| leaf.token.class == closer) {
+            children.push(Element::Leaf(self.leaves.pop_front().unwrap()));
+        }
+        Node { kind, children }
+    }
+}
+
+/// Groups the elements of a file or block into statements, each ending
+/// with its terminator.  Trivia and terminators not belonging to any
+/// statement are left as they are.
+fn statements(elements : Vec<Element>) -> Vec<Element> {
+    let mut grouped = vec![];
+    let mut statement : Vec<Element> = vec![];
+    let finish = |statement : &mut Vec<Element>, grouped : &mut Vec<Element>| {
+        let empty = statement.iter()
+            .all(|element| element.is_trivia() || element.class() == Some(TokenType::Term));
+        if empty {
+            grouped.append(statement);
+        } else {
+            grouped.push(Element::Node(Node {
+                kind: NodeKind::Statement,
+                children: std::mem::take(statement)
+            }));
+        }
+    };
+    for element in elements {
+        let terminates = element.class() == Some(TokenType::Term);
+        statement.push(element);
+        if terminates {
+            finish(&mut statement, &mut grouped);
+        }
+    }
+    finish(&mut statement, &mut grouped);
+    grouped
+}
+
+/// Lexes a piece of code, keeping every character of it, and
+/// builds its concrete syntax tree.
+pub fn parse(source : &str, filename : &str, issues : &mut Diagnostics) -> Tree {
+    let leaves = lexer::lex_lossless(source, filename, issues).into_iter()
+        .map(|token| {
+            let location = &token.location.location;
+            let start = location.byte_offset.unwrap_or(0);
+            let text = source[start..start + location.span.unwrap_or(0)].to_owned();
+            Leaf { token, text }
+        })
+        .collect();
+    let mut builder = Builder { leaves, closers: vec![] };
+
+    let mut children = vec![];
+    loop {
+        children.extend(statements(builder.children()));
+        // Closers that were never opened.
+        match builder.leaves.pop_front() {
+            Some(leaf) => children.push(Element::Leaf(leaf)),
+            None => break
+        }
+    }
+    Tree {
+        root: Node { kind: NodeKind::Root, children },
+        filename: filename.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod lossless {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn sources_are_reproduced() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let directories = ["samples", "tests/expect_success", "tests/expect_fail"];
+        for directory in directories.iter().map(|directory| root.join(directory)) {
+            for entry in fs::read_dir(directory).unwrap() {
+                let path = entry.unwrap().path();
+                if path.extension().is_none_or(|extension| extension != "vh") { continue; }
+                let source = fs::read_to_string(&path).unwrap();
+                let tree = parse(&source, "test.vh", &mut Diagnostics::new());
+                assert!(tree.to_string() == source, "`{}' is not reproduced.", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn brackets_and_blocks_are_kept() {
+        let tree = parse("-- Sum.\na = (1 + 2) {- c -}\nb = x where:\n  x = 3\n",
+            "test.vh", &mut Diagnostics::new());
+        let kinds : Vec<NodeKind> = tree.root.children.iter()
+            .filter_map(|child| match child {
+                Element::Node(node) => Some(node.kind),
+                Element::Leaf(_) => None
+            })
+            .collect();
+        assert_eq!(kinds, [NodeKind::Statement, NodeKind::Statement]);
+
+        let leaves = tree.root.leaves();
+        let comments : Vec<&str> = leaves.iter()
+            .filter(|leaf| leaf.token.class == TokenType::Comment)
+            .map(|leaf| leaf.text.as_str())
+            .collect();
+        assert_eq!(comments, ["-- Sum.", "{- c -}"]);
+
+        let statement = |i : usize| match &tree.root.children[i] {
+            Element::Node(node) => node.clone(),
+            Element::Leaf(_) => unreachable!()
+        };
+        let has = |node : &Node, kind| node.children.iter()
+            .any(|child| matches!(child, Element::Node(node) if node.kind == kind));
+        assert!(has(&statement(2), NodeKind::Paren));
+        assert!(has(&statement(3), NodeKind::Block));
+    }
+
+    #[test]
+    fn ast_is_derived() {
+        let source = "!allow warnings\nf : Nat -> Nat\nf n = (n + 1) * 2 -- Double.\n";
+        let tree = parse(source, "test.vh", &mut Diagnostics::new());
+
+        let mut issues = Diagnostics::new();
+        let stream = lexer::lex(source, "test.vh", &mut issues).into_iter()
+            .filter(|token| token.class != TokenType::Pragma)
+            .collect();
+        let expected = parser::parse(stream, "test.vh", &mut issues);
+        assert_eq!(tree.to_ast(&mut Diagnostics::new()).to_string(), expected.to_string());
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{issue, site::Site};
+use issue::Diagnostics;
 
 use super::token;
-use token::{Token, TokenType};
+use token::{Token, TokenType, Indent};
 
+use std::cmp::Ordering;
 use std::collections::VecDeque;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -40,12 +42,77 @@ impl RegexExt for Regex {
 /// All chars that may constitute an ident.
 const IDENT_CHARS : &str = r"\p{L}\?!'\-_";
 
-// TODO: Parse symbols with spaces? `:"..."` syntax.
 lazy_static! {
     static ref OP    : Regex = re!(r"\A([,\+\.\*\|\\/\&%\$\^\~<¬=@>\-]+|:{2,})");
     static ref IDENT : Regex = re!(&format!(r"\A([{id}][{id}\p{{N}}]*)", id=IDENT_CHARS));
     static ref SYM   : Regex = re!(r"\A(:[^\s]+)");
-    static ref NUM   : Regex = re!(r"\A(\-?(?:(?:0[xX][0-9a-f]+)|(?:0[bB][01]+)|(?:0[Oo][0-7]+)|(?:(?:[0-9]+(?:\.[0-9]+)?(?:e[\+\-]?[0-9]+)?))))");
+    // Pragmas run up to the end of the line, or to a comment on it.
+    static ref PRAGMA : Regex = re!(r"\A(!(?:allow|deny)[ \t](?:[ \t]*(?:[^\s\-{]|\-[^\s\-]|\{[^\s\-]))*)");
+    static ref BLOCK : Regex = re!(r"\A((?:do|where|with|let|in):)(?:\s|\z)");
+    static ref NUM   : Regex = re!(concat!(r"\A(\-?(?:",
+        r"(?:0[xX][0-9a-fA-F](?:_?[0-9a-fA-F])*)|",
+        r"(?:0[bB][01](?:_?[01])*)|",
+        r"(?:0[oO][0-7](?:_?[0-7])*)|",
+        r"(?:[0-9](?:_?[0-9])*(?:\.[0-9](?:_?[0-9])*)?(?:[eE][\+\-]?[0-9](?:_?[0-9])*)?)",
+        r"))"));
+    /// Anything directly following a number that makes it malformed.
+    static ref NUM_TAIL : Regex = re!(r"\A((?:[\p{L}\p{N}_]|\.\p{N})+)");
+}
+
+/// Largest exponent a natural literal may have, e.g. `1e10000'.
+const MAX_NATURAL_EXPONENT : u32 = 10_000;
+
+/// Message and note for a numeric literal, followed by the given tail of
+/// characters, that cannot be parsed, or `None' if it can be.
+fn malformed_numeric(literal : &str, tail : &str) -> Option<(String, &'static str)> {
+    let word = format!("{}{}", literal, tail).to_ascii_lowercase();
+    let unsigned = word.trim_start_matches('-');
+    if !tail.is_empty() {
+        let note = if tail.starts_with('_') || literal.ends_with('_') {
+            "Digits may only be separated by single underscores, e.g. `1_000_000'."
+        } else if unsigned.starts_with("0x") {
+            "Hexadecimal literals may only contain the digits `0' to `9' and `a' to `f'."
+        } else if unsigned.starts_with("0o") {
+            "Octal literals may only contain the digits `0' to `7'."
+        } else if unsigned.starts_with("0b") {
+            "Binary literals may only contain the digits `0' and `1'."
+        } else if tail.starts_with('.') {
+            "Numbers may have at most one decimal point."
+        } else if tail.starts_with(['e', 'E']) {
+            "Exponents must have digits, e.g. `1e3' or `2.5e-3'."
+        } else {
+            "Numbers may not be directly followed by letters or digits, \
+             use `*' to multiply."
+        };
+        return Some((format!("Malformed numeric literal `{}{}'.", literal, tail), note));
+    }
+
+    let is_radix = ["0x", "0o", "0b"].iter().any(|prefix| unsigned.starts_with(prefix));
+    let exponent = match unsigned.split_once('e') {
+        Some((mantissa, exponent)) if !is_radix && !mantissa.contains('.') => exponent,
+        _ => return None
+    };
+    if exponent.starts_with('-') {
+        return None;
+    }
+    let exponent = exponent.trim_start_matches('+').replace('_', "");
+    match exponent.parse::<u32>() {
+        Ok(n) if n <= MAX_NATURAL_EXPONENT => None,
+        _ => Some((format!("Exponent of natural literal `{}' is too large.", literal),
+            "Natural literals are exact, and may have exponents of at most \
+             10000.  Write it as a real, e.g. `1.0e20000', if an approximation \
+             will do."))
+    }
+}
+
+/// Closing delimiter of the block comment opened at the beginning
+/// of the string, if one is opened.  Openers are as long as their closers.
+fn block_comment_closer(string : &str) -> Option<&'static str> {
+    if string.starts_with("--*") {
+        Some("*--")
+    } else if string.starts_with("{-") {
+        Some("-}")
+    } else { None }
 }
 
 macro_rules! try_match {
@@ -68,9 +135,119 @@ macro_rules! try_match {
     };
 }
 
+/// How a piece of a string literal ends.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StrEnd {
+    /// Closing quote.
+    Quote,
+    /// Opening of an embedded expression, `:{' or `#{'.
+    Embed,
+    /// End of the source, the string is never closed.
+    Unclosed,
+}
+
+/// Lexes the contents of a string, up to its closing quote or an embedded
+/// expression.  Gives the contents, how they end, and the number of bytes
+/// and columns taken up by them (along with what ended them).
+/// Expressions are only embedded when `interpolate' is set.
+/// The contents begin at the `line', `col' and byte `offset' given,
+/// and malformed escapes are reported to `issues' and left out.
+fn lex_string(partial : &str, interpolate : bool,
+              line : usize, col : usize, offset : usize,
+              filename : &str, issues : &mut Diagnostics) -> (String, StrEnd, usize, usize) {
+    let mut contents = String::new();
+    let mut columns = 0;
+    let mut chars = partial.char_indices().peekable();
+
+    while let Some((i, character)) = chars.next() {
+        match character {
+            '"' => return (contents, StrEnd::Quote, i + 1, columns + 1),
+            ':' | '#' if interpolate && partial[i + 1..].starts_with('{') =>
+                return (contents, StrEnd::Embed, i + 2, columns + 2),
+            '\\' => {
+                let next = match chars.next() {
+                    Some((_, next)) => next,
+                    None => return (contents, StrEnd::Unclosed, partial.len(), columns + 1)
+                };
+                let escaped = match next {
+                    '\\' | '"' | '\'' | ':' | '#' | '{' | '}' => Ok(next),
+                    'r' => Ok('\r'),
+                    'n' => Ok('\n'),
+                    't' => Ok('\t'),
+                    'b' => Ok('\x08'),
+                    '0' => Ok('\0'),
+                    'x' => {
+                        // Exactly two hexadecimal digits.
+                        let digits : String = partial[i + 2..].chars()
+                            .take(2)
+                            .take_while(char::is_ascii_hexdigit)
+                            .collect();
+                        for _ in 0..digits.len() { chars.next(); }
+                        if digits.len() == 2 {
+                            Ok(u8::from_str_radix(&digits, 16).unwrap() as char)
+                        } else {
+                            Err(("Malformed `\\x' escape.",
+                                 "Write exactly two hexadecimal digits, e.g. `\\x41'."))
+                        }
+                    },
+                    'u' => {
+                        // One to six hexadecimal digits, in braces.
+                        let rest = &partial[i + 2..];
+                        let braced = rest.strip_prefix('{')
+                            .and_then(|rest| rest.find('}').map(|end| &rest[..end]))
+                            .filter(|digits| !digits.contains(['"', '\n']));
+                        match braced {
+                            Some(digits) => {
+                                for _ in 0..digits.chars().count() + 2 { chars.next(); }
+                                let valid = (1..=6).contains(&digits.len())
+                                    && digits.chars().all(|c| c.is_ascii_hexdigit());
+                                if valid {
+                                    u32::from_str_radix(digits, 16).ok()
+                                        .and_then(std::char::from_u32)
+                                        .ok_or(("Escape is not a valid Unicode character.",
+                                                "Surrogates, and values past `\\u{10FFFF}', \
+                                                 are not characters."))
+                                } else {
+                                    Err(("Malformed `\\u{...}' escape.",
+                                         "Write one to six hexadecimal digits, e.g. `\\u{1F600}'."))
+                                }
+                            },
+                            None => Err(("Malformed `\\u{...}' escape.",
+                                         "Write the hexadecimal digits in braces, e.g. `\\u{E9}'."))
+                        }
+                    },
+                    _ => Err(("Unknown escape sequence.",
+                              "Write `\\\\' for a backslash."))
+                };
+
+                let end = chars.peek().map_or(partial.len(), |&(j, _)| j);
+                let width = partial[i..end].width();
+                match escaped {
+                    Ok(escaped) => contents.push(escaped),
+                    Err((message, note)) => {
+                        issue!(LexError, V0036,
+                            Site::single_line(line, col + columns,
+                                width, end - i, offset + i).with_filename(filename),
+                            "{}", message)
+                            .note(note)
+                            .report(issues);
+                    }
+                }
+                columns += width;
+            },
+            c => {
+                contents.push(c);
+                columns += c.width().unwrap_or(2);
+            }
+        }
+    }
+    (contents, StrEnd::Unclosed, partial.len(), columns)
+}
+
 /// Takes a piece of code (as a &str) and returns
 /// the generated token-stream (as a VecDeque<Token>).
-pub fn lex(string : &str, filename : &str) -> VecDeque<Token> {
+/// Any issues found are reported to `issues`.
+pub fn lex(string : &str, filename : &str, issues : &mut Diagnostics) -> VecDeque<Token> {
     let mut token_stream : VecDeque<Token> = VecDeque::new();
 
     let mut current_char_ptr = 0;
@@ -79,6 +256,10 @@ pub fn lex(string : &str, filename : &str) -> VecDeque<Token> {
     let mut partial : &str;
     let mut line : usize = 1;
     let mut col  : usize = 1;
+    // Braces open in each embedded expression of interpolated strings,
+    //  where the strings embedding them were opened, and the strings
+    //  inside of them which are never closed.
+    let mut embeds : Vec<(usize, Site, Diagnostics)> = vec![];
 
     // Step through
     while current_char_ptr < string_size {
@@ -92,6 +273,53 @@ pub fn lex(string : &str, filename : &str) -> VecDeque<Token> {
 
         let two_chars = partial.get(0..2).unwrap_or("\0\0");
 
+        // Consume (nested) block comments, `--* ... *--' or `{- ... -}'.
+        if let Some(closer) = block_comment_closer(partial) {
+            // Closers of the comments still open, and where they were opened.
+            let mut open = vec![(closer, Site::single_line(line, col,
+                closer.len(), closer.len(), current_char_ptr))];
+            let mut i = closer.len();
+            col += i;
+            while let Some(&(closer, ref opener)) = open.last() {
+                let rest = &partial[i..];
+                // A `--*--' fence closes a `--*' comment, rather than
+                //  opening a comment nested in it.
+                let closing = if closer == "*--" && rest.starts_with("--*--") {
+                    Some(5)
+                } else if rest.starts_with(closer) {
+                    Some(closer.len())
+                } else { None };
+
+                if let Some(length) = closing {
+                    open.pop();
+                    i += length;
+                    col += length;
+                } else if let Some(inner) = block_comment_closer(rest) {
+                    let length = inner.len();
+                    open.push((inner, Site::single_line(line, col,
+                        length, length, current_char_ptr + i)));
+                    i += length;
+                    col += length;
+                } else if let Some(character) = rest.chars().next() {
+                    if character == '\n' {
+                        line += 1;
+                        col = 1;
+                    } else {
+                        col += character.width().unwrap_or(0);
+                    }
+                    i += character.len_utf8();
+                } else {
+                    issue!(LexError, V0031, opener.with_filename(filename),
+                        "Block comment is never closed.")
+                        .note(&format!("Close the comment with `{}'.", closer))
+                        .report(issues);
+                    break;
+                }
+            }
+            current_char_ptr += i;
+            continue;
+        }
+
         // Consume EON comment:
         if two_chars == "#!" || two_chars == "--" {
             let old_char_ptr = current_char_ptr;
@@ -111,9 +339,19 @@ pub fn lex(string : &str, filename : &str) -> VecDeque<Token> {
 
             continue;
         }
-        // TODO: Consume multi-line comments (`--* ... *--`).
-        // TODO: Lex `with:` `let:` `in:` `where:` `do:`
-        // indentation blocks.
+        // File-level pragmas start at the beginning of a line.
+        if col == 1 {
+            let matched = try_match!(token_stream, partial,
+                PRAGMA, TokenType::Pragma,
+                current_char_ptr, line, col);
+            if matched.is_some() { continue; }
+        }
+
+        // Indentation blocks, closed in `layout'.
+        let matched = try_match!(token_stream, partial,
+            BLOCK, TokenType::LBlock,
+            current_char_ptr, line, col);
+        if matched.is_some() { continue; }
 
         let vec_brack = match two_chars {
             "[|" => Some(TokenType::LVec),
@@ -143,6 +381,86 @@ pub fn lex(string : &str, filename : &str) -> VecDeque<Token> {
         let first_char = partial.chars().nth(0)
             .expect("Empty program was trying to be lexed."); // This shouldn't happen.
 
+        // Strings, and the rest of an interpolated string
+        //  after one of its embedded expressions.
+        let resuming = match embeds.last() {
+            Some((0, quote, _)) if first_char == '}' => Some(quote.clone()),
+            _ => None
+        };
+        let resumes_string = resuming.is_some();
+        // Quoted symbols, e.g. `:"hello world"', are lexed like strings,
+        //  but never interpolated.
+        let symbol = two_chars == ":\"";
+        if first_char == '"' || resumes_string || symbol {
+            if resumes_string {
+                // Strings left open in the closed expression are reported
+                //  now, unless it is itself embedded in an open one.
+                let (_, _, mut inner) = embeds.pop().unwrap();
+                match embeds.last_mut() {
+                    Some((_, _, outer)) => outer.append(&mut inner),
+                    None => issues.append(&mut inner)
+                }
+            }
+            let opener = if symbol { 2 } else { 1 };
+            let quote = resuming.unwrap_or_else(||
+                Site::single_line(line, col, opener, opener, current_char_ptr));
+            let rest = &partial[opener..];
+            let (start_col, start_ptr) = (col + opener, current_char_ptr + opener);
+
+            let mut found = Diagnostics::new();
+            let (mut contents, mut end, mut bytes, mut columns) = lex_string(rest,
+                !symbol, line, start_col, start_ptr, filename, &mut found);
+            if end == StrEnd::Unclosed {
+                // Lexing continues after the line the string was opened on.
+                if let Some(newline) = rest.find('\n') {
+                    found = Diagnostics::new();
+                    let (truncated, _, _, width) = lex_string(&rest[..newline],
+                        !symbol, line, start_col, start_ptr, filename, &mut found);
+                    contents = truncated;
+                    bytes = newline;
+                    columns = width;
+                }
+                let unclosed = issue!(LexError, V0035, quote.with_filename(filename),
+                    "{} is never closed.", if symbol { "Symbol" } else { "String" })
+                    .fix(Site::single_line(line, start_col + columns,
+                        0, 0, start_ptr + bytes).with_filename(filename), "\"");
+                // A string in an embedded expression is only reported once
+                //  the expression is closed, as it is otherwise part of the
+                //  string embedding it, which is reported instead.
+                match embeds.last_mut() {
+                    Some((_, _, inner)) => inner.push(unclosed),
+                    None => issues.push(unclosed)
+                }
+                end = StrEnd::Quote;
+            }
+            issues.append(&mut found);
+
+            let class = match (resumes_string, end) {
+                _ if symbol => TokenType::Sym,
+                (false, StrEnd::Embed) => TokenType::StrHead,
+                (true,  StrEnd::Embed) => TokenType::StrMid,
+                (true,  _) => TokenType::StrTail,
+                (false, _) => TokenType::Str,
+            };
+            if symbol { contents.insert(0, ':'); }
+            if end == StrEnd::Embed { embeds.push((0, quote, Diagnostics::new())); }
+            token_stream.push_back(Token::new(
+                class, &contents,
+                Site::single_line(line, col,
+                    columns + opener, bytes + opener, current_char_ptr)));
+            col += columns + opener;
+            current_char_ptr += bytes + opener;
+            continue;
+        }
+        // Braces inside of an embedded expression.
+        if let Some((depth, _, _)) = embeds.last_mut() {
+            match first_char {
+                '{' => *depth += 1,
+                '}' => *depth -= 1,
+                _ => ()
+            }
+        }
+
         let single_char_token = match first_char {
             '(' => Some(TokenType::LParen),
             ')' => Some(TokenType::RParen),
@@ -169,79 +487,30 @@ pub fn lex(string : &str, filename : &str) -> VecDeque<Token> {
             continue;
         }
 
-        if first_char == '"' {
-            let mut contents = String::new();
-
-            let mut eos = false;
-            let mut i = 1;
-            let old_col = col;
-            let old_char_ptr = current_char_ptr;
-            while !eos {  // Spaghetti
-                if let Some(character) = partial.chars().nth(i) {
-                    if character == '"' {
-                        current_char_ptr += 1;
-                        col += 1;
-                        eos = true;
-                    } else if character == '\\' {
-                        if let Some(next) = partial.chars().nth(i + 1) {
-                            let escaped : String = match next {
-                               '\\' => String::from("\\"),
-                                'r' => String::from("\r"),
-                                'n' => String::from("\n"),
-                                't' => String::from("\t"),
-                                'b' => String::from("\x08"),
-                                '0' => String::from("\0"),
-                                'x' => {
-                                    if let Some(code) = partial
-                                        .get((current_char_ptr + 2)
-                                            ..(current_char_ptr + 4)) {
-                                        i += 2;
-                                        col += 2;
-                                        current_char_ptr += 2;
-                                        (u8::from_str_radix(code, 16)
-                                            .expect("Malformed hex.") as char)
-                                            .to_string()
-                                    } else { String::new() }
-                                }
-                                c => c.to_string()
-                            };
-                            i += 1;
-                            col += 1;
-                            current_char_ptr += 1;
-                            contents.push_str(&escaped);
-                            continue;
-                        } else {
-                            eos = true;
-                            // TODO Error: Unexpected EOS!
-                        }
-                    } else {
-                        contents.push(character);
-                        i += 1;
-                        col += character.width().unwrap_or(2);
-                        current_char_ptr += character.len_utf8();
-                        continue;
-                    }
-                } else {
-                    eos = true;
-                    // TODO Error: Unexpected EOS!
-                }
-                i += 1;
-                current_char_ptr += 1;
-                col += 1;
-            }
-            token_stream.push_back(Token::new(
-                TokenType::Str, &contents,
-                Site::single_line(line, old_col,
-                    col - old_col,
-                    current_char_ptr - old_char_ptr,
-                    old_char_ptr)));
-            continue;
-        }
-
         let matched = try_match!(token_stream, partial,
             NUM, TokenType::Num,
             current_char_ptr, line, col);
-        if matched.is_some() { continue; }
+        if let Some(token) = matched {
+            let literal = token.string.clone();
+            let tail = NUM_TAIL.first_match(&partial[literal.len()..])
+                .unwrap_or_default();
+            if let Some((message, note)) = malformed_numeric(&literal, &tail) {
+                // The malformed literal is skipped over entirely, and
+                //  stands in as a zero so that parsing may continue.
+                let site = Site::single_line(line, col - literal.width(),
+                    literal.width() + tail.width(), literal.len() + tail.len(),
+                    current_char_ptr - literal.len());
+                issue!(LexError, V0040, site.with_filename(filename), "{}", message)
+                    .note(note)
+                    .report(issues);
+                let token = token_stream.back_mut().unwrap();
+                token.string = String::from("0");
+                token.location = site;
+                current_char_ptr += tail.len();
+                col += tail.width();
+            }
+            continue;
+        }
 
         let matched = try_match!(token_stream, partial,
             OP, TokenType::Op,
@@ -258,10 +527,11 @@ pub fn lex(string : &str, filename : &str) -> VecDeque<Token> {
             current_char_ptr, line, col);
         if let Some(token) = matched {
             if two_chars == ":)" {
-                issue!(LexWarn, token.location.with_filename(filename),
+                issue!(LexWarn, V0002, token.location.with_filename(filename),
                     "Nice smiley-face, but are you sure you wanted to \
                      use a `Symbol' here?  Use `:\")\"` to be more explicit.")
-                     .print();
+                     .fix(token.location.with_filename(filename), ":\")\"")
+                     .report(issues);
             }
             continue;
         }
@@ -270,14 +540,501 @@ pub fn lex(string : &str, filename : &str) -> VecDeque<Token> {
         if partial.is_char_boundary(0) { col += 1 }
     }
 
-    let mut last_location = Site::new();
-    if let  Some(last_token) = token_stream.back() {
-        last_location = last_token.location.to_owned();
+    // Strings left open are nested in the first of them, which is
+    //  the only one reported.
+    if let Some((_, quote, _)) = embeds.first() {
+        issue!(LexError, V0035, quote.with_filename(filename),
+            "String is never closed.")
+            .note("An expression embedded in the string is never closed with `}'.")
+            .report(issues);
     }
 
+    // The end of the stream is placed on the last token, not counting
+    //  line-feeds, which end on the line after their own.
+    let last_location = token_stream.iter().rev()
+        .find(|token| token.class != TokenType::Term)
+        .map_or_else(Site::new, |token| token.location.to_owned());
+
     token_stream.push_back(Token::new(
         TokenType::EOF, "\0",
         last_location));
 
-    token_stream
+    layout(token_stream, string, filename, issues)
+}
+
+/// Length of the (nested) block comment opened at the beginning of the
+/// string, up to the end of the string if it is never closed.
+fn block_comment_length(string : &str) -> usize {
+    let mut open : Vec<&str> = vec![];
+    let mut i = 0;
+    while let Some(character) = string[i..].chars().next() {
+        let rest = &string[i..];
+        let closing = match open.last() {
+            Some(&"*--") if rest.starts_with("--*--") => Some(5),
+            Some(closer) if rest.starts_with(closer) => Some(closer.len()),
+            _ => None
+        };
+        if let Some(length) = closing {
+            open.pop();
+            i += length;
+            if open.is_empty() { break; }
+        } else if let Some(closer) = block_comment_closer(rest) {
+            open.push(closer);
+            i += closer.len();
+        } else {
+            i += character.len_utf8();
+        }
+    }
+    i
+}
+
+/// Site of the source between two byte offsets.
+fn source_site(source : &str, start : usize, end : usize) -> Site {
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line = source[..start].matches('\n').count() + 1;
+    let col = source[line_start..start].width() + 1;
+    let text = &source[start..end];
+
+    let mut site = Site::single_line(line, col, text.width(), text.len(), start);
+    if let Some(last_newline) = text.rfind('\n') {
+        site.location.lines = Some(text.matches('\n').count() + 1);
+        site.location.last_column = Some(text[last_newline + 1..].width() + 1);
+    }
+    site
+}
+
+/// Splits whitespace and comments, skipped over by the lexer, into tokens.
+fn trivia(source : &str, start : usize, end : usize, stream : &mut VecDeque<Token>) {
+    let mut offset = start;
+    while offset < end {
+        let rest = &source[offset..end];
+        let (class, length) = if block_comment_closer(rest).is_some() {
+            (TokenType::Comment, block_comment_length(rest))
+        } else if rest.starts_with("--") || rest.starts_with("#!") {
+            (TokenType::Comment, rest.find('\n').unwrap_or(rest.len()))
+        } else {
+            let comment = rest.char_indices().find(|&(i, _)| {
+                let from = &rest[i..];
+                block_comment_closer(from).is_some()
+                    || from.starts_with("--") || from.starts_with("#!")
+            });
+            (TokenType::Whitespace, comment.map_or(rest.len(), |(i, _)| i))
+        };
+        let text = &rest[..length];
+        stream.push_back(Token::new(class, text,
+            source_site(source, offset, offset + length)));
+        offset += length;
+    }
+}
+
+/// Lexes a piece of code just as `lex' does, but keeps whitespace and
+/// comments as trivia tokens, so that the source can be reproduced
+/// exactly from the sites of the tokens.  Virtual tokens (the ends of
+/// indentation blocks, and of the file) span no source at all.
+pub fn lex_lossless(string : &str, filename : &str, issues : &mut Diagnostics)
+    -> VecDeque<Token> {
+    let stream = lex(string, filename, issues);
+    let mut lossless = VecDeque::with_capacity(2 * stream.len());
+    let mut offset = 0;
+
+    for mut token in stream {
+        if token.class == TokenType::EOF {
+            trivia(string, offset, string.len(), &mut lossless);
+            token.location = source_site(string, string.len(), string.len());
+            lossless.push_back(token);
+            break;
+        }
+        let location = &token.location.location;
+        let start = location.byte_offset.unwrap_or(offset).max(offset);
+        if location.span.unwrap_or(0) > 0 {
+            trivia(string, offset, start, &mut lossless);
+            offset = location.eos().unwrap_or(start).max(offset);
+        }
+        lossless.push_back(token);
+    }
+    lossless
+}
+
+/// Indentation of a line, up to the byte offset given.
+/// Anything other than tabs before the offset counts as spaces.
+fn indentation(source : &str, offset : usize) -> Vec<Indent> {
+    let start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+
+    let mut runs = vec![];
+    for character in source[start..offset].chars() {
+        if character == '\t' {
+            runs.push(Indent::Tab);
+            continue;
+        }
+        let width = character.width().unwrap_or(0) as u32;
+        if width == 0 { continue; }
+        match runs.last_mut() {
+            Some(Indent::Spaces(spaces)) => *spaces += width,
+            _ => runs.push(Indent::Spaces(width))
+        }
+    }
+    runs
+}
+
+/// Indentation of a token, from the start of its line.
+fn token_indentation(source : &str, token : &Token) -> Vec<Indent> {
+    indentation(source, token.location.location.byte_offset.unwrap_or(0))
+}
+
+/// Leading whitespace of the line a token is on.
+fn line_indentation(source : &str, token : &Token) -> Vec<Indent> {
+    let offset = token.location.location.byte_offset.unwrap_or(0);
+    let start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &source[start..offset];
+    indentation(source, offset - line.trim_start_matches([' ', '\t']).len())
+}
+
+/// How far `a' is indented compared to `b', or `None' if
+/// neither begins with the other, as tabs and spaces are mixed.
+fn compare_indentation(a : &[Indent], b : &[Indent]) -> Option<Ordering> {
+    for (i, (x, y)) in a.iter().zip(b).enumerate() {
+        if x == y { continue; }
+        return match (x, y) {
+            (Indent::Spaces(n), Indent::Spaces(m))
+                if n < m && i + 1 == a.len() => Some(Ordering::Less),
+            (Indent::Spaces(n), Indent::Spaces(m))
+                if n > m && i + 1 == b.len() => Some(Ordering::Greater),
+            _ => None
+        };
+    }
+    Some(a.len().cmp(&b.len()))
+}
+
+/// An open indentation block.
+struct Block {
+    /// Indentation of the statements in the block.
+    indent : Vec<Indent>,
+    /// Number of brackets open when the block was opened.
+    depth : usize,
+}
+
+/// Inserts the virtual tokens of indentation blocks.  A block opened by
+/// `do:', `where:', `with:', `let:' or `in:' holds every line indented
+/// as far as its first statement, separated by terminators.  Lines indented
+/// further continue the statement above them, and the first line indented
+/// less closes the block (`RBlock').  Brackets opened in a block must be
+/// closed in it, and newlines inside of them are insignificant.
+fn layout(stream : VecDeque<Token>, source : &str, filename : &str, issues : &mut Diagnostics)
+    -> VecDeque<Token> {
+    let mut laid : VecDeque<Token> = VecDeque::with_capacity(stream.len());
+    let mut blocks : Vec<Block> = vec![];
+    let mut depth = 0;
+
+    // A block was just opened, and its first token decides its indentation.
+    let mut opening = false;
+    // Newline before the current token, if it is significant.
+    let mut newline : Option<Token> = None;
+    let mut last_site = Site::new();
+    // Indentation of the line the block was opened on.
+    let mut opener_indent = vec![];
+
+    let close = |site : &Site| Token::new(TokenType::RBlock, "", site.end());
+    let mixed = |token : &Token, issues : &mut Diagnostics| {
+        // Point at the indentation leading up to the token.
+        let location = &token.location.location;
+        let offset = location.byte_offset.unwrap_or(0);
+        let start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let site = Site::single_line(location.line.unwrap_or(1), 1,
+            location.column.unwrap_or(1) - 1, offset - start, start)
+            .with_filename(filename);
+        issue!(LexError, V0032, site,
+            "Indentation mixes tabs and spaces inconsistently.")
+            .note("Indent every line in a block the same way, \
+                   with either tabs or spaces.")
+            .report(issues);
+    };
+
+    for token in stream {
+        let significant = opening
+            || blocks.last().is_some_and(|block| block.depth == depth);
+        if token.class == TokenType::Term && token.string == "\n" && significant {
+            newline.get_or_insert(token);
+            continue;
+        }
+        if token.class == TokenType::EOF {
+            if opening { laid.push_back(close(&last_site)); }
+            for _ in blocks.drain(..) {
+                laid.push_back(close(&last_site));
+            }
+            laid.push_back(token);
+            break;
+        }
+
+        // Embedded expressions of interpolated strings are bracketed too.
+        let closing_bracket = matches!(token.class,
+            TokenType::RParen | TokenType::RBrack
+            | TokenType::RBrace | TokenType::RVec
+            | TokenType::StrMid | TokenType::StrTail);
+
+        if opening {
+            opening = false;
+            let held = newline.take();
+            let indent = token_indentation(source, &token);
+            // A newline right after the opener does not separate anything,
+            //  unless nothing is indented under the opener.
+            match compare_indentation(&indent, &opener_indent) {
+                _ if closing_bracket => laid.push_back(close(&last_site)),
+                Some(Ordering::Greater) => blocks.push(Block { indent, depth }),
+                None => {
+                    mixed(&token, issues);
+                    blocks.push(Block { indent, depth });
+                },
+                Some(_) => {
+                    laid.push_back(close(&last_site));
+                    laid.extend(held);
+                },
+            }
+        } else if let Some(newline) = newline.take() {
+            let indent = token_indentation(source, &token);
+            loop {
+                let block = match blocks.last() {
+                    Some(block) if block.depth == depth => block,
+                    _ => {
+                        laid.push_back(newline);
+                        break;
+                    }
+                };
+                match compare_indentation(&indent, &block.indent) {
+                    Some(Ordering::Greater) => break,
+                    Some(Ordering::Equal) => {
+                        laid.push_back(newline);
+                        break;
+                    },
+                    Some(Ordering::Less) => {
+                        blocks.pop();
+                        laid.push_back(close(&last_site));
+                        // Dedenting to between two blocks.
+                        let between = blocks.last()
+                            .filter(|block| block.depth == depth)
+                            .is_some_and(|block| compare_indentation(&indent, &block.indent)
+                                == Some(Ordering::Greater));
+                        if between {
+                            issue!(LexError, V0033, token.location.start().with_filename(filename),
+                                "Indentation does not match any enclosing block.")
+                                .note("Line up the statement with the others in its block.")
+                                .report(issues);
+                            laid.push_back(newline);
+                            break;
+                        }
+                    },
+                    None => {
+                        mixed(&token, issues);
+                        laid.push_back(newline);
+                        break;
+                    }
+                }
+            }
+        }
+
+        if closing_bracket {
+            depth = depth.saturating_sub(1);
+            while blocks.last().is_some_and(|block| block.depth > depth) {
+                blocks.pop();
+                laid.push_back(close(&last_site));
+            }
+        }
+        match token.class {
+            TokenType::LParen | TokenType::LBrack
+            | TokenType::LBrace | TokenType::LVec
+            | TokenType::StrHead | TokenType::StrMid => depth += 1,
+            TokenType::LBlock => {
+                opening = true;
+                opener_indent = line_indentation(source, &token);
+            },
+            _ => ()
+        }
+        last_site = token.location.clone();
+        laid.push_back(token);
+    }
+    laid
+}
+
+#[cfg(test)]
+mod blocks {
+    use super::*;
+
+    /// Classes of the tokens lexed, the newlines and virtual tokens
+    /// written as `\n', `{' and `}', and any other token as `.'.
+    fn layout_of(code : &str) -> (String, Diagnostics) {
+        let mut issues = Diagnostics::new();
+        let classes = lex(code, "test.vh", &mut issues).iter()
+            .map(|token| match token.class {
+                TokenType::Term   => '\n',
+                TokenType::LBlock => '{',
+                TokenType::RBlock => '}',
+                TokenType::EOF    => '$',
+                _ => '.'
+            })
+            .collect();
+        (classes, issues)
+    }
+
+    #[test]
+    fn statements_are_separated() {
+        let (classes, issues) = layout_of("a = do:\n  b\n\n  c\nd");
+        assert_eq!(classes, "..{.\n.}\n.$");
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn indented_lines_continue() {
+        let (classes, _) = layout_of("a = do:\n  b\n    + c\n  d\n");
+        assert_eq!(classes, "..{...\n.}$");
+    }
+
+    #[test]
+    fn blocks_nest() {
+        let code = "a = f where: b = do:\n               c\n             d\ne";
+        let (classes, issues) = layout_of(code);
+        assert_eq!(classes, "...{..{.}\n.}\n.$");
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn brackets_close_blocks() {
+        let (classes, _) = layout_of("a = (do: b\n         c) d");
+        assert_eq!(classes, "...{.\n.}..$");
+    }
+
+    #[test]
+    fn empty_blocks() {
+        let (classes, _) = layout_of("a = do:\nb");
+        assert_eq!(classes, "..{}\n.$");
+    }
+
+    #[test]
+    fn mixed_indentation() {
+        let (_, issues) = layout_of("a = do:\n\tb\n        c");
+        assert!(issues.iter().any(|issue| issue.code == crate::codes::V0032));
+        let (_, issues) = layout_of("a = do:\n    b = do:\n        c\n      d");
+        assert!(issues.iter().any(|issue| issue.code == crate::codes::V0033));
+    }
+}
+
+#[cfg(test)]
+mod strings {
+    use super::*;
+
+    fn tokens(code : &str) -> Vec<(TokenType, String)> {
+        let mut issues = Diagnostics::new();
+        lex(code, "test.vh", &mut issues).into_iter()
+            .map(|token| (token.class, token.string))
+            .collect()
+    }
+
+    fn issues(code : &str) -> Vec<crate::codes::Code> {
+        let mut issues = Diagnostics::new();
+        lex(code, "test.vh", &mut issues);
+        issues.iter().map(|issue| issue.code).collect()
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(tokens(r#""a\tb\x41\"\:{c}\u{e9}\u{1F600}""#)[0],
+            (TokenType::Str, String::from("a\tbA\":{c}\u{e9}\u{1F600}")));
+        assert!(issues(r#""\\ \' \# \{ \} \r \n \b \0""#).is_empty());
+    }
+
+    #[test]
+    fn malformed_escapes() {
+        use crate::codes::V0036;
+        assert_eq!(issues(r#""\q \x4 \xzz \u41 \u{} \u{1234567} \u{D800}""#), vec![V0036; 7]);
+        // Malformed escapes are left out.
+        assert_eq!(tokens(r#""a\qb\u{}c""#)[0].1, "abc");
+    }
+
+    #[test]
+    fn quoted_symbols() {
+        assert_eq!(tokens(r#":"hello world" :")" :"a\"b:{c}""#)[..3], [
+            (TokenType::Sym, String::from(":hello world")),
+            (TokenType::Sym, String::from(":)")),
+            (TokenType::Sym, String::from(":a\"b:{c}"))]);
+        assert_eq!(issues(":\"open\n"), vec![crate::codes::V0035]);
+    }
+
+    #[test]
+    fn unclosed_strings() {
+        use crate::codes::V0035;
+        assert_eq!(issues("a = \"b\nc = 2"), vec![V0035]);
+        // Lexing continues on the next line.
+        let lexed = tokens("a = \"b\nc = 2");
+        assert_eq!(lexed[2], (TokenType::Str, String::from("b")));
+        assert_eq!(lexed[3].0, TokenType::Term);
+        assert_eq!(lexed[4], (TokenType::Ident, String::from("c")));
+        assert_eq!(issues("a = \"b:{c"), vec![V0035]);
+        // Strings left open inside of an open string are part of it.
+        assert_eq!(issues(r##"a = "#{""##), vec![V0035]);
+        assert_eq!(issues(r##"a = "#{ "#{""##), vec![V0035]);
+        // Unless the expression embedding them is closed.
+        assert_eq!(issues("a = \"#{ f \"b\n} c\""), vec![V0035]);
+    }
+
+    #[test]
+    fn interpolation() {
+        let lexed = tokens(r#""a:{b}c#{ {d} }e""#);
+        let classes : Vec<TokenType> = lexed.iter().map(|(class, _)| *class).collect();
+        assert_eq!(classes, vec![
+            TokenType::StrHead, TokenType::Ident,
+            TokenType::StrMid, TokenType::LBrace, TokenType::Ident, TokenType::RBrace,
+            TokenType::StrTail, TokenType::EOF]);
+        assert_eq!(lexed[0].1, "a");
+        assert_eq!(lexed[2].1, "c");
+        assert_eq!(lexed[6].1, "e");
+    }
+}
+
+#[cfg(test)]
+mod numbers {
+    use super::*;
+    use crate::codes::{Code, V0040};
+
+    fn lexed(code : &str) -> (Vec<String>, Vec<Code>) {
+        let mut issues = Diagnostics::new();
+        let numbers = lex(code, "test.vh", &mut issues).into_iter()
+            .filter(|token| token.class == TokenType::Num)
+            .map(|token| token.string)
+            .collect();
+        (numbers, issues.iter().map(|issue| issue.code).collect())
+    }
+
+    #[test]
+    fn literals() {
+        let (numbers, codes) = lexed("1_000 0xFF_ff 0B10 0o7 2.5E-3 1e1_0 -3");
+        assert_eq!(numbers, ["1_000", "0xFF_ff", "0B10", "0o7", "2.5E-3", "1e1_0", "-3"]);
+        assert!(codes.is_empty());
+    }
+
+    #[test]
+    fn malformed_literals() {
+        for code in &["0b102", "0o8", "0xFG", "0x", "1__000", "1_", "2a", "1e", "1.2.3", "1e20000"] {
+            let (numbers, codes) = lexed(code);
+            assert_eq!(codes, vec![V0040], "`{}' is not malformed.", code);
+            assert_eq!(numbers, ["0"]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod pragmas {
+    use super::*;
+
+    #[test]
+    fn comments_end_pragmas() {
+        for code in &["!allow V0002, TypeWarn -- Note.\n",
+                      "!allow V0002, TypeWarn {- Note. -}\n",
+                      "!allow V0002, TypeWarn\n"] {
+            let mut issues = Diagnostics::new();
+            let stream = lex(code, "test.vh", &mut issues);
+            assert_eq!(stream[0].class, TokenType::Pragma);
+            assert_eq!(stream[0].string, "!allow V0002, TypeWarn", "in `{}'", code);
+            assert!(stream.iter().skip(1)
+                .all(|token| token.class != TokenType::Ident));
+            assert!(issues.is_empty());
+        }
+    }
 }
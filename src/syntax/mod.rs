@@ -21,27 +21,52 @@ pub mod lexer;
 /// Converts a token-stream into a nested AST.
 pub mod parser;
 
+/// Lossless concrete syntax tree, keeping all
+/// whitespace and comments, for tooling.
+pub mod cst;
+
 /// Tree static analysis.
 #[macro_use]
 pub mod analysis;
 
 use std::fs;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+
+use crate::{issue, site::Site};
+use crate::source::SourceMap;
+use crate::lints::LintLevels;
+use token::TokenType;
+use issue::Diagnostics;
 
 #[cfg(feature="debug")]
 use token::ShowStream;
 
-pub fn parse_source(code : &str, filename : &str) -> ast::Root {
+/// Lexes, parses and analyses a piece of source code.
+/// Any warnings are kept in the `issues` of the returned tree.
+/// Add `code' to a `SourceMap' for issues to show excerpts of it.
+pub fn parse_source(code : &str, filename : &str) -> Result<ast::Root, Diagnostics> {
+    let mut issues = Diagnostics::new();
+
     // First lex:
     #[cfg(feature="debug")]
     println!("Code:\n{}\n", code);
 
-    let stream = lexer::lex(&code, filename);
+    let stream = lexer::lex(&code, filename, &mut issues);
+
+    // Pragmas apply to the whole file, and are not part of the program.
+    let (pragmas, stream) : (VecDeque<_>, VecDeque<_>) = stream.into_iter()
+        .partition(|token| token.class == TokenType::Pragma);
+    let mut lints = LintLevels::new();
+    for pragma in &pragmas {
+        lints.pragma(pragma, filename, &mut issues);
+    }
 
     #[cfg(feature="debug")]
     println!("Stream:\n{}\n", stream.to_string());
 
-    let mut tree = parser::parse(stream, filename);
+    // Statements which could not be parsed are left out of the tree,
+    //  so the rest of it may still be analysed for further issues.
+    let mut tree = parser::parse(stream, filename, &mut issues);
 
     #[allow(unused_variables)]
     let transformations = transformations![
@@ -55,21 +80,29 @@ pub fn parse_source(code : &str, filename : &str) -> ast::Root {
         TYPE_RESOLUTION
     ];
 
-    analysis::replace(&mut tree, transformations);
+    analysis::replace(&mut tree, transformations, &mut issues);
+
+    lints.apply(&mut issues);
+    issues.sort();
+    if issues.has_errors() { return Err(issues); }
 
     #[cfg(feature="debug")]
     println!("AST:\n{}\n", tree);
 
-    tree
-
+    tree.issues = issues;
+    tree.lints = lints;
+    Ok(tree)
 }
 
 /// Parses a given file, calling various methods from
-/// the `syntax` sub-module.
-pub fn parse_file(filename : &str) -> ast::Root {
-    let code = fs::read_to_string(filename)
-        .expect("Could not open file for reading.");
-    parse_source(&code, filename)
+/// the `syntax` sub-module.  The source read is kept in `sources`.
+pub fn parse_file(filename : &str, sources : &mut SourceMap) -> Result<ast::Root, Diagnostics> {
+    match fs::read_to_string(filename) {
+        Ok(code) => parse_source(&sources.add(filename.into(), code).content, filename),
+        Err(error) => Err(issue!(LexError, V0001,
+            Site::new().with_filename(filename),
+            "Could not open file for reading: {}.", error)
+                .fatal()
+                .into())
+    }
 }
-
-
@@ -94,10 +94,12 @@ impl<'a> Default for PrecedenceTable<'a> {
             op(  "^",160, Side::Right,   2),
             op(  "*",150, Side::Left,    2),
             op(  "/",150, Side::Left,    2),
+            op("div",150, Side::Left,    2),
             op("mod",150, Side::Left,    2),
             op(  "&",140, Side::Left,    2),
             op(  "|",130, Side::Left,    2),
             op(  "+",120, Side::Left,    2),
+            op( "++",120, Side::Left,    2),
             op(  "-",120, Side::Left,    2),
             op( "\\",120, Side::Left,    2),
             op( "->",110, Side::Right,   2),
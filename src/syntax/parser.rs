@@ -7,6 +7,7 @@ use super::operators;
 
 use crate::{issue, site};
 use site::{Site, Location};
+use issue::{Diagnostics, Fallible};
 
 use token::{Token, TokenType};
 use ast::Nodes;
@@ -15,8 +16,13 @@ lazy_static! {
     static ref EXPR_TERM : HashSet<TokenType> = {
         let mut set = HashSet::new();
         set.insert(TokenType::RParen);
+        set.insert(TokenType::RBrack);
+        set.insert(TokenType::RVec);
         set.insert(TokenType::EOF);
         set.insert(TokenType::Term);
+        set.insert(TokenType::RBlock);
+        set.insert(TokenType::StrMid);
+        set.insert(TokenType::StrTail);
         set
     };
 }
@@ -37,8 +43,9 @@ fn location_range(loc_begin : &Location, loc_end : &Location) -> Location {
         *line = loc_begin.line.unwrap();
         *span = loc_end.eos().unwrap()
             - loc_begin.byte_offset.unwrap();
+        // Across multiple lines, count only the columns of the last line.
         *columns = loc_end.column.unwrap() + loc_end.columns.unwrap()
-            - loc_begin.column.unwrap();
+            - if *lines > 1 { 1 } else { loc_begin.column.unwrap() };
         *column = loc_begin.column.unwrap();
         *byte_offset = loc_begin.byte_offset.unwrap();
     }
@@ -46,8 +53,27 @@ fn location_range(loc_begin : &Location, loc_end : &Location) -> Location {
     loc_final
 }
 
-pub fn parse(stream : VecDeque<Token>, file : &str) -> ast::Root {
-    let mut environment = ParseEnvironment::new(stream, file);
+/// Operands of an application of a binary operator, if `node' is one.
+fn binary_operands<'n>(node : &'n Nodes, op : &str) -> Option<(&'n Nodes, &'n Nodes)> {
+    let call = node.call()?;
+    if !call.is_binary() { return None; }
+    let partial = call.callee.call()?;
+    if partial.callee.ident()?.value != op { return None; }
+    Some((&partial.operands[0], &call.operands[0]))
+}
+
+/// Items of a comma separated list, e.g. `a, b, c', which is parsed as a tuple.
+fn comma_separated(node : &Nodes) -> Vec<&Nodes> {
+    match node {
+        Nodes::Tuple(tuple) => tuple.elements.iter().collect(),
+        _ => vec![node]
+    }
+}
+
+/// Parses a token-stream into a tree, reporting any issues to `issues`.
+/// Statements which fail to parse are reported and left out of the tree.
+pub fn parse(stream : VecDeque<Token>, file : &str, issues : &mut Diagnostics) -> ast::Root {
+    let mut environment = ParseEnvironment::new(stream, file, issues);
     environment.start();
     environment.root
 }
@@ -57,22 +83,27 @@ struct ParseEnvironment<'a> {
     pub stream : VecDeque<Token>,
     pub optable : operators::PrecedenceTable<'a>,
     pub file : &'a str,
+    pub issues : &'a mut Diagnostics,
 
     ignore_newline : bool,
+    /// Number of indentation blocks being parsed.
+    blocks : usize,
     site : Site,
     eof_token : Token
 }
 
 impl<'a> ParseEnvironment<'a> {
-    pub fn new(stream : VecDeque<Token>, file : &'a str) -> Self {
+    pub fn new(stream : VecDeque<Token>, file : &'a str, issues : &'a mut Diagnostics) -> Self {
         ParseEnvironment {
             root: ast::Root::new(file),
             eof_token: stream.iter().last().unwrap().to_owned(),
             stream,
             optable: operators::PrecedenceTable::new(),
             file,
+            issues,
 
             ignore_newline: false,
+            blocks: 0,
             site: Site::single_line(1, 1, 1, 1, 0),
         }
     }
@@ -88,16 +119,43 @@ impl<'a> ParseEnvironment<'a> {
                 current = self.stream.get(0);
                 continue;
             }
-            let e = self.expr(0);
-            self.root.branches.push(e);
+            match self.expr(0) {
+                Ok(e) => self.root.branches.push(e),
+                Err(issue) => {
+                    issue.report(self.issues);
+                    self.synchronise();
+                }
+            }
             current = self.stream.get(0);
         }
         self.shift();
     }
 
+    /// Recover from a bad statement, by skipping to the next one.
+    /// The rest of any blocks the bad statement was in are skipped too.
+    fn synchronise(&mut self) {
+        self.ignore_newline = false;
+        while let Some(token) = self.stream.front() {
+            match token.class {
+                TokenType::Term if self.blocks == 0 => break,
+                TokenType::EOF => {
+                    self.blocks = 0;
+                    break;
+                },
+                TokenType::LBlock => self.blocks += 1,
+                TokenType::RBlock => self.blocks = self.blocks.saturating_sub(1),
+                _ => ()
+            }
+            self.shift();
+        }
+    }
+
     fn shift(&mut self) -> Token {
-        if self.stream.is_empty() {
-            self.stream.push_back(self.eof_token.clone());
+        // The end of the stream is never shifted off of it, so
+        //  there is always a token left to look at.
+        if self.stream.front().is_none_or(|token| token.class == TokenType::EOF) {
+            self.site = self.eof_token.location.to_owned();
+            return self.eof_token.clone();
         }
         let shifted = self.stream.pop_front().unwrap();
         self.site = shifted.location.to_owned();
@@ -113,14 +171,14 @@ impl<'a> ParseEnvironment<'a> {
     // TODO: Generate call nodes with accurate location data.
     //  Currently this is only done in `func_apply`.
 
-    fn null_den(&mut self, token : &Token) -> Nodes {
+    fn null_den(&mut self, token : &Token) -> Fallible<Nodes> {
         let loc = token.location.to_owned();
-        match token.class {
+        Ok(match token.class {
             TokenType::Op | TokenType::Ident => {
                 let is_op = self.optable.exists(&token.string);
                 if is_op {
                     let prefix = self.optable.lookup(&token.string, 1);
-                    return match self.stream[0].class {
+                    return Ok(match self.stream[0].class {
                         TokenType::RParen => {
                             ast::IdentNode::new(&token.string, loc)
                         },
@@ -137,22 +195,26 @@ impl<'a> ParseEnvironment<'a> {
                                         ast::IdentNode::new("flip", loc.to_owned()),
                                         vec![ast::IdentNode::new(&token.string, loc)],
                                         self.site.to_owned()),
-                                    vec![self.expr(500)],
+                                    vec![self.expr(500)?],
                                     self.site.to_owned())
                             } else {
                                 ast::CallNode::new(
                                     ast::IdentNode::new(&token.string, loc),
-                                    vec![self.expr(500)],
+                                    vec![self.expr(500)?],
                                     self.site.to_owned())
                             }
                         }
-                    };
+                    });
                 }
                 ast::IdentNode::new(&token.string, loc)
             },
             TokenType::Num => ast::NumNode::new(&*token.string, loc),
             TokenType::Str => ast::StrNode::new( &token.string,  loc),
             TokenType::Sym => ast::SymNode::new( &token.string,  loc),
+            TokenType::LBlock => self.block(token)?,
+            TokenType::LBrack => self.set(token)?,
+            TokenType::LVec => self.vector(token)?,
+            TokenType::StrHead => self.interpolation(token)?,
             TokenType::LParen => {
                 let maybe_current = self.stream.get(0);
                 if let Some(current) = maybe_current {
@@ -171,46 +233,226 @@ impl<'a> ParseEnvironment<'a> {
                             panic!("All tokens should have width.");
                         }
 
-                        return ast::NilNode::new(nil_loc);
+                        return Ok(ast::NilNode::new(nil_loc));
                     } else if current.class == TokenType::EOF {
-                        self.expect(TokenType::RParen, maybe_current);
+                        self.expect(TokenType::RParen, maybe_current)?;
                     }
                 } else {
-                    self.expect(TokenType::RParen, None);
+                    self.expect(TokenType::RParen, None)?;
                 }
 
                 self.ignore_newline = true;
                 self.skip_newlines();
-                let expr = self.expr(0);
+                let mut expr = self.expr(0)?;
+                if let Nodes::Call(call) = &mut expr {
+                    call.parenthesised = true;
+                }
                 self.skip_newlines();
                 self.ignore_newline = false;
-                self.expect(TokenType::RParen, self.stream.get(0));
+                self.expect(TokenType::RParen, self.stream.get(0))?;
                 self.shift();
                 expr
             }
             _ => {
-                issue!(ParseError, token.location.with_filename(self.file),
+                issue!(ParseError, V0003, token.location.with_filename(self.file),
                     "`{}` has no null-denotation.",
                     token.class)
                         .note("Cannot be used as a prefix / left-of-expression.")
-                        .print();
+                        .report(self.issues);
                 ast::NilNode::new(loc)
             }
+        })
+    }
+
+    /// Parses the statements of an indentation block, up to its end.
+    fn block(&mut self, opener : &Token) -> Fallible<Nodes> {
+        let ignore_newline = self.ignore_newline;
+        self.ignore_newline = false;
+        self.blocks += 1;
+
+        let mut statements = vec![];
+        loop {
+            match self.stream.front().map(|token| token.class) {
+                Some(TokenType::Term) => { self.shift(); },
+                Some(TokenType::RBlock) => break,
+                Some(TokenType::EOF) | None =>
+                    self.expect(TokenType::RBlock, self.stream.front())?,
+                _ => statements.push(self.expr(0)?)
+            }
         }
+        let closer = self.shift();
+        self.blocks -= 1;
+        self.ignore_newline = ignore_newline;
+
+        let mut site = opener.location.clone();
+        site.location = location_range(&opener.location.location,
+                                       &closer.location.location);
+        Ok(ast::BlockNode::new(opener.string.trim_end_matches(':'), statements, site))
+    }
+
+    /// Parses elements separated by semicolons or newlines, up to
+    /// the `closer' of a set or vector, and gives the site of them all.
+    fn elements(&mut self, opener : &Token, closer : TokenType) -> Fallible<(Vec<Nodes>, Site)> {
+        let ignore_newline = self.ignore_newline;
+        self.ignore_newline = false;
+
+        let mut elements = vec![];
+        loop {
+            match self.stream.front().map(|token| token.class) {
+                Some(TokenType::Term) => { self.shift(); },
+                Some(class) if class == closer => break,
+                Some(TokenType::EOF) | None =>
+                    self.expect(closer, self.stream.front())?,
+                _ => elements.push(self.expr(0)?)
+            }
+        }
+        let closer = self.shift();
+        self.ignore_newline = ignore_newline;
+
+        let mut site = opener.location.clone();
+        site.location = location_range(&opener.location.location,
+                                       &closer.location.location);
+        Ok((elements, site))
+    }
+
+    /// Parses the elements of a set literal, e.g. `[ 1; 2; 3 ]', which
+    /// are separated by semicolons or newlines, up to its closing bracket.
+    fn set(&mut self, opener : &Token) -> Fallible<Nodes> {
+        let (elements, site) = self.elements(opener, TokenType::RBrack)?;
+        // A set-builder is the only element of its set.
+        if let Some(body) = elements.iter().find(|element| binary_operands(element, "=>").is_some()) {
+            if elements.len() > 1 {
+                return Err(issue!(ParseError, V0041, body.site().with_filename(self.file),
+                    "Set-builder must be the only element of its set.")
+                    .note("Write the other elements as a separate set, \
+                           and take the union of the two."));
+            }
+            return self.set_builder(&elements[0], site);
+        }
+        Ok(ast::SetNode::new(elements, site))
+    }
+
+    /// Parses the elements of a vector literal, e.g. `[| 1; 2; 3 |]'.
+    fn vector(&mut self, opener : &Token) -> Fallible<Nodes> {
+        let (elements, site) = self.elements(opener, TokenType::RVec)?;
+        Ok(ast::VecNode::new(elements, site))
     }
 
-    fn expr(&mut self, right_prec : i32) -> Nodes {
+    /// Splits the body of a set-builder, `head => qualifiers => ...',
+    /// into the expression giving each element (the head), the
+    /// variables it binds, and the predicates its elements satisfy.
+    /// The head may bind a variable itself, e.g. `n : Nat'.
+    fn set_builder(&mut self, body : &Nodes, site : Site) -> Fallible<Nodes> {
+        let mut qualifiers = vec![];
+        let mut head = body;
+        while let Some((left, right)) = binary_operands(head, "=>") {
+            qualifiers.splice(0..0, comma_separated(right));
+            head = left;
+        }
+
+        let mut generators = vec![];
+        let element = match binary_operands(head, ":") {
+            Some((variable, domain)) => {
+                generators.push(self.generator(variable, domain)?);
+                variable.to_owned()
+            },
+            None => head.to_owned()
+        };
+        let mut conditions = vec![];
+        for qualifier in qualifiers {
+            match binary_operands(qualifier, "<-") {
+                Some((variable, domain)) => generators.push(self.generator(variable, domain)?),
+                None => conditions.push(qualifier.to_owned())
+            }
+        }
+        if generators.is_empty() {
+            return Err(issue!(ParseError, V0041, body.site().with_filename(self.file),
+                "Set-builder does not bind any variables.")
+                .note("Draw a variable from a set, e.g. `n <- Nat'."));
+        }
+
+        // Every predicate must be satisfied.
+        let condition = conditions.into_iter().reduce(|left, right| {
+            let mut call_site = left.site();
+            call_site.location = location_range(&left.location(), &right.location());
+            ast::CallNode::new(
+                ast::CallNode::new(ast::IdentNode::new("and", right.site()),
+                    vec![left], call_site.clone()),
+                vec![right], call_site)
+        });
+        Ok(ast::BuilderNode::new(element, generators, condition, site))
+    }
+
+    /// Variable of a set-builder, drawn from `domain'.
+    fn generator(&self, variable : &Nodes, domain : &Nodes) -> Fallible<ast::Generator> {
+        match variable {
+            Nodes::Ident(ident) => Ok(ast::Generator {
+                variable: ident.to_owned(),
+                domain: domain.to_owned()
+            }),
+            _ => Err(issue!(ParseError, V0041, variable.site().with_filename(self.file),
+                "Only variables may be bound by a set-builder, not a {}.",
+                variable.node_type()))
+        }
+    }
+
+    /// Parses the embedded expressions and the rest of
+    /// an interpolated string, e.g. `"Hello, :{name}!"'.
+    fn interpolation(&mut self, head : &Token) -> Fallible<Nodes> {
+        let ignore_newline = self.ignore_newline;
+        self.ignore_newline = true;
+
+        let mut segments = vec![];
+        let mut piece = head.clone();
+        loop {
+            if !piece.string.is_empty() {
+                segments.push(ast::StrNode::new(&piece.string, piece.location.clone()));
+            }
+            if piece.class == TokenType::StrTail { break; }
+
+            // The lexer has already reported the string as never closed.
+            self.skip_newlines();
+            if self.stream.front().is_none_or(|token| token.class == TokenType::EOF) {
+                break;
+            }
+            segments.push(self.expr(0)?);
+            self.skip_newlines();
+            match self.stream.front().map(|token| token.class) {
+                Some(TokenType::StrMid) | Some(TokenType::StrTail) => {
+                    piece = self.shift();
+                },
+                Some(TokenType::EOF) => break,
+                _ => self.expect(TokenType::StrTail, self.stream.front())?
+            }
+        }
+        self.ignore_newline = ignore_newline;
+
+        let mut site = head.location.clone();
+        site.location = location_range(&head.location.location,
+                                       &piece.location.location);
+        Ok(ast::InterpNode::new(segments, site))
+    }
+
+    fn expr(&mut self, right_prec : i32) -> Fallible<Nodes> {
         let mut popped = self.shift();
         while !self.stream.is_empty() && self.ignore_newline && popped.string == "\n" {
             popped = self.shift();
         }
-        let mut left = self.null_den(&popped);
+        if popped.class == TokenType::EOF {
+            return Err(issue!(ParseError, V0004, popped.location.with_filename(self.file),
+                "Unexpected end of stream.")
+                .note("An expression was expected here."));
+        }
+        let mut left = self.null_den(&popped)?;
+        while self.indexes(&left) {
+            left = self.index(left)?;
+        }
 
         if self.ignore_newline { self.skip_newlines(); }
         if self.stream.is_empty()
             || self.stream[0].class == TokenType::EOF
             || self.stream[0].class == TokenType::Term
-            { return left; }
+            { return Ok(left); }
 
 
         while self.optable.precedence(&self.stream[0].string).unwrap_or(190) > right_prec {
@@ -227,15 +469,44 @@ impl<'a> ParseEnvironment<'a> {
             if let Some(op) = maybe_op {
                 let cloned = operators::Operator::new(next, op.precedence, op.associativity, 2);
                 self.shift();
-                left = self.left_den(left, cloned);
+                left = self.left_den(left, cloned)?;
             } else {  // Function call.
-                left = self.func_apply(left);
+                left = self.func_apply(left)?;
             }
         }
-        return left;
+        return Ok(left);
+    }
+
+    /// Checks if a bracket directly follows `left', e.g. `p[0]', which
+    /// indexes it.  With a space between them, `p [0]' applies `p' to
+    /// a set.
+    fn indexes(&self, left : &Nodes) -> bool {
+        self.stream.front().is_some_and(|token| token.class == TokenType::LBrack
+            && token.location.location.byte_offset.is_some()
+            && token.location.location.byte_offset == left.location().eos())
     }
 
-    fn func_apply(&mut self, mut left : Nodes) -> Nodes {
+    /// Parses an index, so that `p[i]' is the same as `index i p'.
+    fn index(&mut self, left : Nodes) -> Fallible<Nodes> {
+        let ignore_newline = self.ignore_newline;
+        self.ignore_newline = true;
+        let opener = self.shift();
+        let position = self.expr(0)?;
+        self.skip_newlines();
+        self.expect(TokenType::RBrack, self.stream.front())?;
+        let closer = self.shift();
+        self.ignore_newline = ignore_newline;
+
+        let left_site = left.site();
+        let mut site = left_site.clone();
+        site.location = location_range(&left_site.location, &closer.location.location);
+        Ok(ast::CallNode::new(
+            ast::CallNode::new(ast::IdentNode::new("index", opener.location),
+                vec![position], left_site),
+            vec![left], site))
+    }
+
+    fn func_apply(&mut self, mut left : Nodes) -> Fallible<Nodes> {
         // What are `first_loc` & `final_loc` for?
         //  They update location of function call nodes to span
         //  a correct number of columns (store first and last column).
@@ -246,25 +517,25 @@ impl<'a> ParseEnvironment<'a> {
         let mut pushed = false;
         if let Nodes::Call(ref mut call) = left {
             if call.operands.is_empty() {
-                let operand_node = self.expr(190);
+                let operand_node = self.expr(190)?;
                 call.operands.push(operand_node);
                 pushed = true;
             }
         }
 
-        if pushed { return left; }
+        if pushed { return Ok(left); }
 
-        let operand_node = self.expr(190);
+        let operand_node = self.expr(190)?;
         let last_site = operand_node.site();
         let mut final_site = first_site.clone();
         final_site.location = location_range(
             &first_site.location,
               &last_site.location);
 
-        ast::CallNode::new(left, vec![operand_node], final_site)
+        Ok(ast::CallNode::new(left, vec![operand_node], final_site))
     }
 
-    fn left_den(&mut self, left : Nodes, op : operators::Operator) -> Nodes {
+    fn left_den(&mut self, left : Nodes, op : operators::Operator) -> Fallible<Nodes> {
         let left_site = left.site();
         let first_apply = ast::CallNode::new(
             ast::IdentNode::new(op.name, self.site.to_owned()),
@@ -272,36 +543,75 @@ impl<'a> ParseEnvironment<'a> {
             self.site.to_owned());
 
         if self.stream[0].class == TokenType::RParen {
-            return first_apply;
+            return Ok(first_apply);
+        }
+
+        // Commas make tuples, e.g. `a, b, c' is the tuple of all three.
+        //  The elements are parsed in turn, so that a tuple in
+        //  parentheses, as in `a, (b, c)', is an element itself.
+        if op.name == "," {
+            let mut elements = vec![first_apply.call().unwrap().operands[0].to_owned()];
+            loop {
+                elements.push(self.expr(op.precedence)?);
+                if self.stream.front().is_none_or(|token| token.string != ",") {
+                    break;
+                }
+                self.shift();
+            }
+            let mut tuple_site = left_site.clone();
+            tuple_site.location = location_range(
+                &left_site.location,
+                &elements.last().unwrap().location());
+            return Ok(ast::TupleNode::new(elements, tuple_site));
         }
 
         let right = self.expr(op.precedence
-            - (if op.is_right() { 1 } else { 0 }));
+            - (if op.is_right() { 1 } else { 0 }))?;
 
         let mut call_site = left_site.clone();
         call_site.location = location_range(
             &left_site.location,
             &right.location());
-        ast::CallNode::new(first_apply, vec![right], call_site)
+        Ok(ast::CallNode::new(first_apply, vec![right], call_site))
     }
 
-    fn expect(&self, tt : TokenType, maybe_t : Option<&Token>) {
+    fn expect(&self, tt : TokenType, maybe_t : Option<&Token>) -> Fallible<()> {
+        // A missing closing bracket can be inserted at the end of the
+        //  stream, or of a statement, but not in place of another token.
+        let closing = match tt {
+            TokenType::RParen => Some(")"),
+            TokenType::RBrack => Some("]"),
+            TokenType::RBrace => Some("}"),
+            TokenType::RVec   => Some("|]"),
+            _ => None
+        };
         if maybe_t.is_none() {
-            fatal!(ParseError,
-                self.stream.iter()
-                    .last().unwrap()
-                    .location
-                    .with_filename(self.file),
-                "Unexpected end of stream.")
-                    .print();
+            let site = self.eof_token.location.with_filename(self.file);
+            let issue = issue!(ParseError, V0004, site,
+                "Unexpected end of stream.");
+            return Err(match closing {
+                Some(text) => issue.fix(site.end(), text),
+                None => issue
+            });
         }
         let t = maybe_t.unwrap();
         if t.class != tt {
-            fatal!(ParseError, t.location.with_filename(self.file),
+            let site = t.location.with_filename(self.file);
+            let issue = issue!(ParseError, V0005, site,
                 "Unexpected token type: `{}`, expected: `{}`.", t.class, tt)
-                    .note("Perhaps you forgot to write something?")
-                    .print();
+                    .note("Perhaps you forgot to write something?");
+            // The end of the stream is placed on the last token.
+            let at = match t.class {
+                TokenType::Term => Some(site.start()),
+                TokenType::EOF  => Some(site.end()),
+                _ => None
+            };
+            return Err(match (closing, at) {
+                (Some(text), Some(at)) => issue.fix(at, text),
+                _ => issue
+            });
         }
+        Ok(())
     }
 }
 
@@ -358,10 +668,259 @@ mod test {
         num_test!("-2.672e-10", l, Numerics::Real(-0.0000000002672));
         num_test!("-67e-4", l, Numerics::Real(-0.0067));
         num_test!("-67e+10", l, Numerics::Integer(-670000000000));
+        num_test!("1_000_000", l, Numerics::Natural(1_000_000));
+        num_test!("0xFF", l, Numerics::Natural(0xff));
+        num_test!("0XdEaD_bEeF", l, Numerics::Natural(0xdead_beef));
+        num_test!("0B1010_0101", l, Numerics::Natural(0b1010_0101));
+        num_test!("-0O7_7", l, Numerics::Integer(-0o77));
+        num_test!("1e3", l, Numerics::Natural(1000));
+        num_test!("25E+2", l, Numerics::Natural(2500));
+        num_test!("25E-1", l, Numerics::Real(2.5));
+        num_test!("1_2.5_0E1_0", l, Numerics::Real(12.5e10));
+        num_test!("1.0e400", l, Numerics::Real(f64::INFINITY));
 
         let s : String = String::from("-6e12");
         let num = ast::NumNode::new(&*s, l);
 
         assert_eq!(num.num().unwrap().value, Numerics::Integer(-6000000000000));
     }
+
+    #[test]
+    fn big_numeric_parsing() {
+        use ast::ToNumeric;
+        use num_bigint::{BigInt, BigUint};
+
+        let l = Site::new();
+        let big = |s : &str| s.parse::<BigUint>().unwrap();
+        let neg = |s : &str| -s.parse::<BigInt>().unwrap();
+
+        num_test!("18446744073709551615", l, Numerics::Natural(usize::MAX));
+        num_test!("18446744073709551616", l,
+            Numerics::BigNatural(big("18446744073709551616")));
+        num_test!("0x10000000000000000", l,
+            Numerics::BigNatural(big("18446744073709551616")));
+        num_test!("-9223372036854775808", l, Numerics::Integer(isize::MIN));
+        num_test!("-9223372036854775809", l,
+            Numerics::BigInteger(neg("9223372036854775809")));
+        num_test!("2e30", l,
+            Numerics::BigNatural(big("2000000000000000000000000000000")));
+
+        // Arithmetic is exact, and demotes results that fit.
+        let max = usize::MAX.to_numeric();
+        assert_eq!(max.clone() + 1usize.to_numeric(),
+            Numerics::BigNatural(big("18446744073709551616")));
+        assert_eq!((max.clone() + 1usize.to_numeric()) - 1usize.to_numeric(), max);
+        assert_eq!(max.clone() * max.clone() - max.clone(),
+            Numerics::BigNatural(big("340282366920938463408034375210639556610")));
+        assert_eq!(0usize.to_numeric() - max.clone(),
+            Numerics::BigInteger(neg("18446744073709551615")));
+        assert_eq!(max + 0.5.to_numeric(), Numerics::Real(usize::MAX as f64 + 0.5));
+    }
+
+    #[test]
+    fn rational_arithmetic() {
+        use ast::{ToNumeric, StaticTypes};
+        use num_rational::BigRational;
+
+        let q = |n : i64, d : i64| Numerics::Rational(
+            BigRational::new(n.into(), d.into()));
+
+        // Division of naturals and integers is exact.
+        assert_eq!(1.to_numeric() / 3.to_numeric(), q(1, 3));
+        assert_eq!(6.to_numeric() / (-4).to_numeric(), q(-3, 2));
+        assert_eq!(6.to_numeric() / 3.to_numeric(), q(2, 1));
+        assert_eq!(q(1, 3) + q(1, 6), q(1, 2));
+        assert_eq!(q(1, 3) * 3.to_numeric(), q(1, 1));
+        assert_eq!(2.to_numeric() - q(5, 2), q(-1, 2));
+        assert_eq!(q(1, 4) + 0.5.to_numeric(), Numerics::Real(0.75));
+
+        // Casts never lose information.
+        assert_eq!(3.to_numeric().cast(&StaticTypes::TRational), Some(q(3, 1)));
+        assert_eq!(q(1, 4).cast(&StaticTypes::TReal), Some(Numerics::Real(0.25)));
+        assert_eq!(q(1, 4).cast(&StaticTypes::TInteger), None);
+        assert_eq!(0.5.to_numeric().cast(&StaticTypes::TRational), None);
+    }
+
+    #[test]
+    fn block_parsing() {
+        let mut issues = Diagnostics::new();
+        let code = "a = f where:\n  b = 1\n  c = do: b\nd";
+        let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+        let root = parse(stream, "test.vh", &mut issues);
+        assert!(issues.is_empty());
+        // The file node, `a = ...' and `d'.
+        assert_eq!(root.branches.len(), 3);
+
+        let assignment = root.branches[1].call().unwrap();
+        let application = assignment.operands[0].call().unwrap();
+        let block = application.operands[0].block().unwrap();
+        assert_eq!(block.keyword, "where");
+        assert_eq!(block.statements.len(), 2);
+
+        let inner = block.statements[1].call().unwrap().operands[0].block().unwrap();
+        assert_eq!(inner.keyword, "do");
+        assert_eq!(inner.statements.len(), 1);
+    }
+
+    #[test]
+    fn set_parsing() {
+        let mut issues = Diagnostics::new();
+        let code = "a = [ 1; f 2\n  [] ]\nb = [\n  :x\n]";
+        let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+        let root = parse(stream, "test.vh", &mut issues);
+        assert!(issues.is_empty());
+
+        let set = root.branches[1].call().unwrap().operands[0].set().unwrap();
+        assert_eq!(set.elements.len(), 3);
+        assert!(set.elements[1].is_call());
+        assert!(set.elements[2].set().unwrap().elements.is_empty());
+
+        let set = root.branches[2].call().unwrap().operands[0].set().unwrap();
+        assert_eq!(set.elements.len(), 1);
+        assert!(set.elements[0].is_sym());
+    }
+
+    #[test]
+    fn set_builder_parsing() {
+        let mut issues = Diagnostics::new();
+        let code = "a = [ n : Nat => n mod 3 == 0 ]\nb = [ 3*n => n <- Nat, n > 2, n < 9 ]";
+        let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+        let root = parse(stream, "test.vh", &mut issues);
+        assert!(issues.is_empty());
+
+        let builder = root.branches[1].call().unwrap().operands[0].builder().unwrap();
+        assert_eq!(builder.element.ident().unwrap().value, "n");
+        assert_eq!(builder.generators.len(), 1);
+        assert_eq!(builder.generators[0].variable.value, "n");
+        assert_eq!(builder.generators[0].domain.ident().unwrap().value, "Nat");
+        assert!(builder.condition.is_some());
+
+        let builder = root.branches[2].call().unwrap().operands[0].builder().unwrap();
+        assert!(builder.element.is_call());
+        assert_eq!(builder.generators[0].variable.value, "n");
+        // Both predicates must hold.
+        let condition = builder.condition.as_ref().unwrap().call().unwrap();
+        assert_eq!(condition.callee.call().unwrap().callee.ident().unwrap().value, "and");
+
+        for code in &["a = [ 2*n => n > 0 ]", "a = [ 1; n : Nat => n > 0 ]", "a = [ 2*n => 3 <- Nat ]"] {
+            let mut issues = Diagnostics::new();
+            let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+            let root = parse(stream, "test.vh", &mut issues);
+            assert_eq!(root.branches.len(), 1, "`{}' was parsed.", code);
+            assert_eq!(issues.iter().map(|issue| issue.code).collect::<Vec<_>>(),
+                       vec![crate::codes::V0041]);
+        }
+    }
+
+    #[test]
+    fn vector_parsing() {
+        let mut issues = Diagnostics::new();
+        let code = "a = [| 1; f 2\n  [||] |]\nb = [| [| 1 |] |]";
+        let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+        let root = parse(stream, "test.vh", &mut issues);
+        assert!(issues.is_empty());
+
+        let vec = root.branches[1].call().unwrap().operands[0].vec().unwrap();
+        assert_eq!(vec.elements.len(), 3);
+        assert!(vec.elements[1].is_call());
+        assert!(vec.elements[2].vec().unwrap().elements.is_empty());
+
+        let vec = root.branches[2].call().unwrap().operands[0].vec().unwrap();
+        assert_eq!(vec.elements[0].vec().unwrap().elements.len(), 1);
+    }
+
+    #[test]
+    fn tuple_parsing() {
+        let mut issues = Diagnostics::new();
+        let code = "a = (1, f 2, \"c\")\nb = f p[0][1]\nc = p [0]";
+        let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+        let root = parse(stream, "test.vh", &mut issues);
+        assert!(issues.is_empty());
+
+        let tuple = root.branches[1].call().unwrap().operands[0].tuple().unwrap();
+        assert_eq!(tuple.elements.len(), 3);
+        assert!(tuple.elements[1].is_call());
+
+        // `f p[0][1]' is `f (index 1 (index 0 p))'.
+        let application = root.branches[2].call().unwrap().operands[0].call().unwrap();
+        assert_eq!(application.callee.ident().unwrap().value, "f");
+        let outer = application.operands[0].call().unwrap();
+        let index = outer.callee.call().unwrap();
+        assert_eq!(index.callee.ident().unwrap().value, "index");
+        assert_eq!(index.operands[0].num().unwrap().value.to_string(), "1");
+        let inner = outer.operands[0].call().unwrap();
+        assert_eq!(inner.callee.call().unwrap().operands[0].num().unwrap().value.to_string(), "0");
+        assert_eq!(inner.operands[0].ident().unwrap().value, "p");
+
+        // With a space, `p' is applied to a set.
+        let application = root.branches[3].call().unwrap().operands[0].call().unwrap();
+        assert!(application.operands[0].is_set());
+    }
+
+    #[test]
+    fn nested_tuple_parsing() {
+        let mut issues = Diagnostics::new();
+        let code = "a = (1, (2, 3))\nb = ((1, 2), 3)\nc = (1, 2, 3)";
+        let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+        let root = parse(stream, "test.vh", &mut issues);
+        assert!(issues.is_empty());
+
+        let tuple = |i : usize| root.branches[i].call().unwrap().operands[0].tuple().unwrap().clone();
+        assert_eq!(tuple(1).elements.len(), 2);
+        assert_eq!(tuple(1).elements[1].tuple().unwrap().elements.len(), 2);
+        assert_eq!(tuple(2).elements.len(), 2);
+        assert_eq!(tuple(2).elements[0].tuple().unwrap().elements.len(), 2);
+        assert_eq!(tuple(3).elements.len(), 3);
+    }
+
+    #[test]
+    fn nested_product_factors() {
+        let mut issues = Diagnostics::new();
+        let code = "A * B * C\n(A * B) * C\nA * (B * C)";
+        let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+        let root = parse(stream, "test.vh", &mut issues);
+        let lengths : Vec<usize> = root.branches[1..].iter()
+            .map(|branch| branch.product_factors().len())
+            .collect();
+        assert_eq!(lengths, [3, 2, 2]);
+    }
+
+    #[test]
+    fn dangling_operator_at_eof() {
+        for code in &["1 +", "x = 1e-", "f = (1 +"] {
+            let mut issues = Diagnostics::new();
+            let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+            parse(stream, "test.vh", &mut issues);
+            assert!(issues.iter().any(|issue| issue.code == crate::codes::V0004),
+                "`{}' does not report an unexpected end of stream.", code);
+        }
+    }
+
+    #[test]
+    fn unclosed_interpolation() {
+        for code in &["a = \"#{", "a = \"#{\"", "a = \"#{ b"] {
+            let mut issues = Diagnostics::new();
+            let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+            parse(stream, "test.vh", &mut issues);
+            let codes : Vec<_> = issues.iter().map(|issue| issue.code).collect();
+            assert_eq!(codes, vec![crate::codes::V0035], "in `{}'", code);
+        }
+    }
+
+    #[test]
+    fn closing_brackets_are_inserted_at_the_end() {
+        let edits = |code : &str| {
+            let mut issues = Diagnostics::new();
+            let stream = crate::syntax::lexer::lex(code, "test.vh", &mut issues);
+            parse(stream, "test.vh", &mut issues);
+            issues.iter()
+                .flat_map(|issue| issue.edits.iter())
+                .map(|edit| (edit.range(), edit.replacement.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(edits("a = (b + 1"), [(Some((10, 10)), String::from(")"))]);
+        assert_eq!(edits("a = [b"), [(Some((6, 6)), String::from("]"))]);
+        // Another bracket is not closed by inserting one before it.
+        assert!(edits("a = (b]").is_empty());
+    }
 }
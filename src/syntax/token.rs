@@ -8,17 +8,17 @@ use {
     std::collections::VecDeque
 };
 
-/// # TODO: Use this.
-/// Way of representing a level of indentation.
-#[allow(dead_code)]
-enum Indent {
+/// Way of representing a level of indentation, as the
+/// runs of tabs and spaces leading up to a token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indent {
     Tab,
     Spaces(u32),
 }
 
 /// Contains all possible types/classes of
 /// lexiacal tokens.
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum TokenType {
     /// Identifiers, variables, function names etc.
     Ident,
@@ -30,6 +30,13 @@ pub enum TokenType {
     Sym,
     /// Strings, enclosed by double quotes ("...").
     Str,
+    /// Beginning of an interpolated string, up to its first
+    /// embedded expression, e.g. `"Hello, :{'.
+    StrHead,
+    /// Piece of an interpolated string between two embedded expressions.
+    StrMid,
+    /// End of an interpolated string, after its last embedded expression.
+    StrTail,
     /// Left Parenthesis.
     LParen,
     /// Rigt Parenthesis.
@@ -46,9 +53,20 @@ pub enum TokenType {
     LVec,
     /// Right vector-list bracket.
     RVec,
+    /// Opening of an indentation block, e.g. `do:'.
+    LBlock,
+    /// Virtual end of an indentation block.
+    RBlock,
     /// Terminator, something that ends a line.
     /// Either a semi-colon (;) or a new-line (\n).
     Term,
+    /// File-level pragma, e.g. `!allow warnings'.
+    Pragma,
+    /// Whitespace (and anything else the lexer ignores),
+    /// only kept in lossless token-streams.
+    Whitespace,
+    /// Line or block comment, only kept in lossless token-streams.
+    Comment,
     /// End Of File, last token in the stream.
     EOF,
 }
@@ -61,6 +79,9 @@ impl fmt::Display for TokenType {
             Self::Op     => "Operator",
             Self::Sym    => "Symbol",
             Self::Str    => "String",
+            Self::StrHead => "String-Head",
+            Self::StrMid  => "String-Middle",
+            Self::StrTail => "String-Tail",
             Self::LParen => "L-Paren",
             Self::RParen => "R-Paren",
             Self::LBrack => "L-Bracket",
@@ -69,7 +90,12 @@ impl fmt::Display for TokenType {
             Self::RBrace => "R-Brace",
             Self::LVec   => "L-Vector",
             Self::RVec   => "R-Vector",
+            Self::LBlock => "L-Block",
+            Self::RBlock => "R-Block",
             Self::Term   => "Terminator",
+            Self::Pragma => "Pragma",
+            Self::Whitespace => "Whitespace",
+            Self::Comment => "Comment",
             Self::EOF    => "End-Of-File",
         };
         write!(f, "{}", printable)
@@ -95,6 +121,12 @@ impl Token {
         Token { class, string: String::from(string), location: site }
     }
 
+    /// Checks if the token is whitespace or a comment, which
+    /// have no meaning to the parser.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.class, TokenType::Whitespace | TokenType::Comment)
+    }
+
     /// Checks if the token represents an atomic datum.
     pub fn is_atomic(&self) -> bool {
         match self.class {
@@ -120,7 +152,7 @@ impl fmt::Display for Token {
         write!(f, "[ {class}:{spaces1}{rep}{spaces2}{l}:{c} ({span}) ]",
             class=self.class, rep=escaped,
             spaces1=" ".repeat(12 - self.class.to_string().width()),
-            spaces2=" ".repeat(30 - escaped.width()),
+            spaces2=" ".repeat(30usize.saturating_sub(escaped.width())),
             l=self.location.location.line.unwrap(),
             c=self.location.location.column.unwrap(),
             span=self.location.location.columns.unwrap())
@@ -0,0 +1,5 @@
+!deny CompWarn
+-- Constant division by zero is warned about, and denied here.
+
+a : Real
+a = 1.0 / 0.0
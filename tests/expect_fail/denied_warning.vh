@@ -0,0 +1,5 @@
+!deny V0002
+-- Denied warnings stop compilation.
+
+face : Sym
+face = :)
@@ -0,0 +1,7 @@
+-- Tuples are indexed from zero.
+
+p : Int * String
+p = (-3, "John")
+
+name : String
+name = p[2]
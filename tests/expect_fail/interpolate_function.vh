@@ -0,0 +1,7 @@
+-- Only strings and numbers may be embedded in strings.
+
+f : Nat -> Nat
+f n = n + 1
+
+s : String
+s = "f is :{f}"
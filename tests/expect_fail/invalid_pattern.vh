@@ -0,0 +1,5 @@
+-- Arguments may only be matched against patterns.
+
+pred : Nat -> Nat
+pred 0 = 0
+pred (n + 1) = n
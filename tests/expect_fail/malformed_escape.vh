@@ -0,0 +1,4 @@
+-- Backslashes begin escape sequences.
+
+path : String
+path = "C:\Users"
@@ -0,0 +1,4 @@
+-- Binary literals may only contain zeros and ones.
+
+mask : Nat
+mask = 0b1012
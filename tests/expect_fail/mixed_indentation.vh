@@ -0,0 +1,5 @@
+-- Lines of a block are indented with tabs, or spaces.
+
+do:
+	1
+        2
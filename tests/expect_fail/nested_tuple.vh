@@ -0,0 +1,4 @@
+-- A nested tuple is not the same as a flat one.
+
+t : Nat * Nat * Nat
+t = (1, (2, 3))
@@ -0,0 +1,5 @@
+-- Patterns cannot bind a variable twice to compare arguments.
+
+same : Nat * Nat -> Nat -> Sym
+same (x, y) x = :true
+same _ _ = :false
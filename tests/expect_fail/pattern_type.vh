@@ -0,0 +1,4 @@
+-- A pattern must be able to match the argument.
+
+first : Nat * Nat -> Nat
+first (a, b, c) = a
@@ -0,0 +1,5 @@
+-- Dividing naturals gives a rational, which is not a natural,
+--  unless the division truncates, as with `div'.
+
+n : Nat
+n = 1 / 2
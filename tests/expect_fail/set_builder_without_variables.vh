@@ -0,0 +1,4 @@
+-- Nothing is drawn from a set, so `n' is never bound.
+
+Evens : Set Nat
+Evens = [ 2*n => n > 0 ]
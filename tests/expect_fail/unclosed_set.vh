@@ -0,0 +1,4 @@
+-- The set is never closed.
+
+s : Set Nat
+s = [ 1; 2; 3
@@ -0,0 +1,4 @@
+-- Strings must be closed before the end of their line.
+
+greeting : String
+greeting = "Hello, World!
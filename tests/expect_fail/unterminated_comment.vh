@@ -0,0 +1,4 @@
+a : Nat
+a = 3
+
+--* The inner comment {- is never closed. *--
@@ -0,0 +1,4 @@
+-- The vector has one element too few.
+
+v : Nat^3
+v = [| 1; 2 |]
@@ -0,0 +1,6 @@
+-- Only `do' blocks can be compiled as yet.
+
+a : Nat
+a = 1 where:
+  x : Nat
+  x = 2
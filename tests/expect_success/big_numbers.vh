@@ -0,0 +1,13 @@
+-- Literals and constant arithmetic beyond 64 bits are exact.
+
+huge : Nat
+huge = 340282366920938463463374607431768211456
+
+product : Nat
+product = 18446744073709551615 * 18446744073709551615
+
+back : Rat
+back = product / 18446744073709551615
+
+tiny : Int
+tiny = 0 - 0x10000000000000000
@@ -0,0 +1,6 @@
+--* Block comments nest --* like this *--,
+    and only end when every comment is closed. *--
+
+{- Haskell-style {- comments -} nest too. -}
+a : Nat
+a = 1 + 2  {- trailing -}
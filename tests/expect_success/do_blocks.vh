@@ -0,0 +1,35 @@
+-- A `do' block gives the value of its last statement.
+a : Nat
+a = do: 1
+
+-- Its variables are only seen within it.
+b : Nat
+b = do:
+  c : Nat
+  c = a + 1
+  c * 2
+
+c : Int
+c = -b
+
+-- Earlier values are discarded.
+d : Nat
+d = do:
+  b
+  a
+
+f : Nat -> Nat
+f 0 = do:
+  y : Nat
+  y = 2
+  y + 1
+f n = do:
+  y : Nat
+  y = n
+  y * 2
+
+-- A block ending in a definition gives nothing.
+e : Empty
+e = do:
+  g : Nat -> Nat
+  g x = f x
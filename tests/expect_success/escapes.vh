@@ -0,0 +1,4 @@
+-- Escape sequences in strings.
+
+escaped : String
+escaped = "\"\u{1F600}\" \x41 \\ \t\n"
@@ -0,0 +1,13 @@
+-- Constant expressions are folded until nothing more can be.
+
+n : Int
+n = -(2 ^ 10 mod 1000) * 3
+
+small : Sym
+small = 1 / 2 < 0.75 and not (n == 72)
+
+greeting : String
+greeting = "Hello, " ++ "World" ++ "!"
+
+same : Sym
+same = greeting == "Hello, World!" or :a /= :b
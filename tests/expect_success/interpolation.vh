@@ -0,0 +1,13 @@
+-- Strings may embed expressions with `:{...}' or `#{...}'.
+
+name : String
+name = "World"
+
+n : Nat
+n = 3
+
+greeting : String
+greeting = "Hello, :{name}!  #{n + 1} times, {not embedded} \:{escaped}."
+
+nested : String
+nested = "a:{ "b:{ "c" }" }d"
@@ -0,0 +1,13 @@
+-- Digit separators, uppercase digits and exponents.
+
+million : Nat
+million = 1_000_000
+
+mask : Nat
+mask = 0xFF_FF + 0B1010 + 0O17
+
+thousand : Nat
+thousand = 1E3
+
+tenth : Real
+tenth = 1_0e-2
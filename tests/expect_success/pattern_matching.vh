@@ -0,0 +1,41 @@
+-- Functions defined by cases, whose clauses are tried in order.
+
+f : Nat -> Nat -> Nat
+f 1 _ = 2
+f _ 1 = 3
+f a b = a + b
+
+x : Nat
+x = f 4 5
+
+-- Constants and constructors start with a capital letter.
+Zero : Nat
+Zero = 0
+
+Succ : Nat -> Nat
+Succ n = n + 1
+
+pred : Nat -> Nat
+pred Zero = Zero
+pred (Succ n) = n
+
+-- Tuples are taken apart by tuple patterns.
+swap : Int * String -> String * Int
+swap (n, s) = (s, n)
+
+is_origin : Nat * Nat -> Sym
+is_origin (0, 0) = :true
+is_origin _ = :false
+
+-- Negative numbers are literals too.
+double : Int -> Int
+double (-1) = -2
+double n = n + n
+
+-- Clauses may give functions of the remaining arguments.
+g : Nat -> Nat -> Nat
+g 0 = pred
+g a b = a + b
+
+y : Nat
+y = g 0 3
@@ -0,0 +1,10 @@
+-- Symbols may be quoted, to contain any text.
+
+greeting : Sym
+greeting = :"hello world"
+
+smiley : Sym
+smiley = :")"
+
+escaped : Sym
+escaped = :"a \"quoted\" :{symbol}\n"
@@ -0,0 +1,26 @@
+-- Division of naturals and integers is exact.
+
+third : Rat
+third = 1 / 3
+
+half : Rational
+half = third + 1 / 6
+
+r : Real
+r = half + 0.25
+
+x : Nat
+x = 7
+
+q : Rat
+q = x / 2
+
+s : String
+s = "q is :{q}"
+
+-- Whereas `div' truncates, so stays within the naturals or integers.
+h : Nat
+h = x div 2
+
+t : Int
+t = (0 - x) div 2
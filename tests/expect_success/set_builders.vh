@@ -0,0 +1,17 @@
+-- Sets given by set-builder notation.
+
+Fizz : Set Nat
+Fizz = [ n : Nat => n mod 3 == 0 ]
+
+Multiples5 : Set Nat
+Multiples5 = [ 5*n => n <- Nat ]
+
+Small : Set Nat
+Small = [ 2*n => n <- [ 1; 2; 3 ], n > 1 ]
+
+Roots : Set Real
+Roots = [ x : Real => x^2 - 3*x - 4 == 0 ]
+
+-- `n' is bound again, as it is only bound within each set-builder.
+n : Nat
+n = 7
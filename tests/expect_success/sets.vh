@@ -0,0 +1,17 @@
+-- Enumerated sets, separated by semicolons or newlines.
+
+primes : Set Nat
+primes = [ 2; 3; 5; 7 ]
+
+bools : Set Sym
+bools = [ :true
+          :false ]
+
+mixed : Set Real
+mixed = [ 1; -2; 2.5 ]
+
+nested : Set (Set Nat)
+nested = [ []; [ 1 + 1 ] ]
+
+none : Set Nat
+none = []
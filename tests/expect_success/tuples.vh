@@ -0,0 +1,42 @@
+-- Tuples, and cartesian products of sets.
+
+prod : Int * Int * String * Real
+prod = (-3, -9, "John", -2.6)
+
+x : Int
+x = prod[0]
+
+name : String
+name = index 2 prod
+
+-- Products of a single set are vectors.
+v : Real * Real
+v = (1.5, 2.5)
+
+w : Real^2
+w = v
+
+pair : Int^2 * String
+pair = ([| -1; -2 |], "a")
+
+first : Int
+first = pair[0][1]
+
+n : Nat
+n = 4
+
+ns : Nat^2
+ns = [| n; 2 |]
+
+m : Nat
+m = ns[1] + n
+
+-- Tuples and products in parentheses are nested.
+t : Nat * (Nat * Nat)
+t = (1, (2, 3))
+
+u : (Nat * Nat) * Nat
+u = ((1, 2), 3)
+
+middle : Nat
+middle = t[1][0]
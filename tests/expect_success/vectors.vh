@@ -0,0 +1,23 @@
+-- Vectors, of a fixed number of elements.
+
+v : Nat^3
+v = [| 1; 2; 3 |]
+
+mixed : Real^2
+mixed = [| 1; 2.5 |]
+
+n : Nat
+n = 4
+
+computed : Nat^2
+computed = [| n; n + 1 |]
+
+none : Nat^0
+none = [||]
+
+nested : (Nat^2)^2
+nested = [| [| 1; 2 |]; [| 3; 4 |] |]
+
+words : String^2
+words = [| "a"
+           "b" |]
@@ -1,11 +1,8 @@
-#![feature(set_stdio)]
-
 use valhallac;
 
 use colored::*;
 
 use std::{fs, path::Path, ffi::OsStr};
-use std::panic;
 
 type DynErr = Box<dyn std::error::Error>;
 
@@ -19,21 +16,35 @@ fn get_source(path : &Path) -> Result<String, ()> {
 
 struct Ratio(u32, u32);
 
+/// Tests expected to succeed, which fail for want of a feature
+/// the compiler does not have yet, and what that feature is.
+const KNOWN_FAILURES : &[(&str, &str)] = &[
+    ("arith_2", "implicit casts of arguments, e.g. from `Nat' to `Int'"),
+    ("arith_3", "implicit casts of arguments, e.g. from `Nat' to `Int'"),
+    ("auto_function", "constant functions, and `map'"),
+    ("io_1", "`import', and `${...}' interpolation"),
+];
+
+fn known_failure(path : &Path) -> Option<&'static str> {
+    let stem = path.file_stem()?.to_string_lossy();
+    KNOWN_FAILURES.iter()
+        .find(|(name, _)| *name == stem)
+        .map(|(_, reason)| *reason)
+}
+
 fn on_vh_files<F>(dir_path : &str, mut lambda : F) -> Result<Ratio, DynErr>
     where F : FnMut(&Path, String) -> bool {
     let (mut passes, mut total) = (0, 0);
-    for entry in fs::read_dir(dir_path)? {
-        if let Ok(path) = entry {
-            let path = path.path();
-            if let Ok(source) = get_source(&path) {
-                total += 1;
-                if lambda(&path, source) { passes += 1 }
-            } else {  // Otherwise just skip.
-                println!("     Skipping `{}'...", path
-                    .file_name().unwrap()
-                    .to_string_lossy()
-                    .underline());
-            }
+    for entry in fs::read_dir(dir_path)?.flatten() {
+        let path = entry.path();
+        if let Ok(source) = get_source(&path) {
+            total += 1;
+            if lambda(&path, source) { passes += 1 }
+        } else {  // Otherwise just skip.
+            println!("     Skipping `{}'...", path
+                .file_name().unwrap()
+                .to_string_lossy()
+                .underline());
         }
     }
     Ok(Ratio(passes, total))
@@ -41,13 +52,6 @@ fn on_vh_files<F>(dir_path : &str, mut lambda : F) -> Result<Ratio, DynErr>
 
 use std::io::prelude::Write;
 
-fn redirect_stderr(file : &str) -> Result<(), DynErr> {
-    let f = fs::File::create(file)?;
-    let sink : Box<dyn Send + Write> = Box::new(f);
-    std::io::set_panic(Some(sink));
-    Ok(())
-}
-
 fn status(good : bool) -> String {
     if good {
         "Ok".green()
@@ -57,45 +61,53 @@ fn status(good : bool) -> String {
 }
 
 fn main() -> Result<(), DynErr> {
-    valhallac::set_panic();
-    redirect_stderr("stderr.log")?;
+    let mut log = fs::File::create("stderr.log")?;
 
     let mut count = 0;
     let mut compile_attempt = |path: &Path, source: String| {
         count += 1;
         let filename = path.to_string_lossy();
         // For the log.
-        eprintln!(" === Compiling: `{}' ===", filename.underline());
+        writeln!(log, " === Compiling: `{}' ===", filename.underline()).ok();
         let prefix = format!("{: >3}. (`{}'):",
             count.to_string().bold(),
             path.file_stem().unwrap()
                 .to_string_lossy()
                 .underline()
                 .white());
-        // Catch errors:
-        let did_panic = panic::catch_unwind(|| unsafe {
-            valhallac::PANIC_MESSAGE = "";
-            let tree = valhallac::parse_source(&source, &filename);
-            if valhallac::PANIC_MESSAGE.is_empty() {
+        // Issues are shown from the source as it was compiled.
+        let mut sources = valhallac::SourceMap::new();
+        sources.add(valhallac::SourceId::from(&*filename), source.clone());
+        // Collect issues:
+        let compiled = valhallac::parse_source(&source, &filename)
+            .and_then(|tree| {
                 // Try to compile.
-                valhallac::compile(&tree);
-                if !valhallac::PANIC_MESSAGE.is_empty() {
-                    panic!("Did not pass: `{}'", valhallac::PANIC_MESSAGE);
-                }
-            } else {
-                panic!("Did not pass: `{}'", valhallac::PANIC_MESSAGE);
-            }
-        });
+                valhallac::compile(&tree).map(|_| ())
+            });
+        if let Err(issues) = &compiled {
+            write!(log, "{}", issues.with_sources(&sources)).ok();
+        }
         print!("{} {} ", prefix, ".".repeat(80 - prefix.len()));
-        did_panic.is_ok()
+        compiled.is_ok()
     };
 
     // Expecting success:
     println!("\n{} {}", "==>".blue().bold(),
         "Expecting compilation success:".white().bold());
+    let (mut known, mut fixed) = (0, 0);
     let succ_ratio = on_vh_files("./expect_success", |path, source| {
         let passed = compile_attempt(path, source);
-        println!("{}", status(passed));
+        match known_failure(path) {
+            Some(reason) if !passed => {
+                known += 1;
+                println!("{} (needs {})", "Known".yellow().bold(), reason);
+            },
+            Some(_) => {
+                fixed += 1;
+                println!("{} (remove it from the known failures)", status(passed));
+            },
+            None => println!("{}", status(passed))
+        }
         passed
     })?;
     println!();
@@ -111,12 +123,18 @@ fn main() -> Result<(), DynErr> {
     println!();
 
     // Results:
-    println!("{}", format!("Success Tests: {}/{}.",
+    println!("{}", format!("Success Tests: {}/{} ({} known failures).",
         succ_ratio.0.to_string().yellow(),
-        succ_ratio.1.to_string().yellow()).bold());
+        succ_ratio.1.to_string().yellow(),
+        known.to_string().yellow()).bold());
     println!("{}", format!("Failure Tests: {}/{}.",
         fail_ratio.0.to_string().yellow(),
         fail_ratio.1.to_string().yellow()).bold());
 
+    // Any test which did not go as expected fails the run.
+    if succ_ratio.0 + known != succ_ratio.1
+    || fail_ratio.0 != fail_ratio.1 || fixed > 0 {
+        std::process::exit(1);
+    }
     Ok(())
 }